@@ -1,27 +1,358 @@
-use anyhow::{Result, bail};
+use crate::branch::Model;
+use crate::counters::Counters;
+use crate::data::{Lengths, Source};
+use crate::distribution::Distribution;
+use crate::histogram::Sampling;
+use crate::input::Format;
+use crate::preset::Preset;
+use crate::report;
+use crate::stats::Estimator;
+use crate::system::{self, Placement};
+use crate::timer::{self, Timer};
+use anyhow::{Context as _, Result, bail};
+use std::collections::VecDeque;
 use std::env;
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
 pub struct Args {
     pub benchmark: Vec<(&'static str, Type)>,
+    pub source: Source,
+    // The data source options as given, for the run metadata.
+    pub source_options: Vec<String>,
     pub unpredictable: bool,
+    pub lengths: Lengths,
+    pub sweep: bool,
+    pub latency: bool,
+    pub threads: Vec<Placement>,
+    pub order_seed: Option<u64>,
+    pub seed: u64,
+    pub count: usize,
+    pub trials: usize,
+    pub passes: Option<usize>,
+    pub warmup: Duration,
+    pub trial_time: Duration,
+    pub time_budget: Option<Duration>,
+    pub timer: Timer,
+    pub sampling: Option<Sampling>,
+    pub discard_interrupted: bool,
+    pub max_spread: f64,
+    pub remeasure: usize,
+    pub estimator: Estimator,
+    pub stats: bool,
+    pub raw: bool,
+    pub format: report::Format,
+    pub chart: Option<PathBuf>,
+    pub highlight: Option<&'static str>,
+    pub counters: Option<Counters>,
+    pub cpu: Option<usize>,
+    pub nice: Option<i32>,
+    pub save_manifest: Option<PathBuf>,
+    pub manifest: String,
 }
 
+#[derive(Copy, Clone)]
 pub enum Type {
+    U8(crate::F<u8>),
+    U16(crate::F<u16>),
     U32(crate::F<u32>),
     U64(crate::F<u64>),
     U128(crate::F<u128>),
+    Usize(crate::F<usize>),
+    I8(crate::F<i8>),
+    I16(crate::F<i16>),
+    I32(crate::F<i32>),
+    I64(crate::F<i64>),
+    I128(crate::F<i128>),
+    Isize(crate::F<isize>),
+}
+
+impl Type {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Type::U8(_) => "u8",
+            Type::U16(_) => "u16",
+            Type::U32(_) => "u32",
+            Type::U64(_) => "u64",
+            Type::U128(_) => "u128",
+            Type::Usize(_) => "usize",
+            Type::I8(_) => "i8",
+            Type::I16(_) => "i16",
+            Type::I32(_) => "i32",
+            Type::I64(_) => "i64",
+            Type::I128(_) => "i128",
+            Type::Isize(_) => "isize",
+        }
+    }
 }
 
 pub fn parse() -> Result<Args> {
-    let mut args = env::args_os();
-    args.next().unwrap();
+    let mut args: VecDeque<OsString> = env::args_os().skip(1).collect();
+
+    // The lines of a manifest are parsed before the rest of the command line,
+    // whose options then take precedence.
+    let mut manifest_len = 0;
+    if let Some(i) = args.iter().position(|arg| arg == "--manifest") {
+        args.remove(i);
+        let Some(path) = args.remove(i) else {
+            bail!("missing value for --manifest");
+        };
+        if args.contains(&OsString::from("--manifest")) {
+            bail!("--manifest may only be given once");
+        }
+        let path = PathBuf::from(path);
+        let manifest = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let mut lines = Vec::new();
+        for line in manifest.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once(' ') {
+                Some((flag, value)) if line.starts_with("--") => {
+                    lines.push(OsString::from(flag));
+                    lines.push(OsString::from(value));
+                }
+                _ => lines.push(OsString::from(line)),
+            }
+        }
+        manifest_len = lines.len();
+        for line in lines.into_iter().rev() {
+            args.push_front(line);
+        }
+    }
+    let command_line_len = args.len() - manifest_len;
 
     let mut benchmark = Vec::new();
+    let mut manifest_benchmark = Vec::new();
+    let mut sources = Sources::default();
+    let mut manifest_sources = Sources::default();
     let mut unpredictable = false;
-    'args: for arg in args {
+    let mut lengths = Lengths::all();
+    let mut sweep = false;
+    let mut latency = false;
+    let mut threads = None;
+    let mut interleave = false;
+    let mut order_seed = None;
+    let mut seed = 1;
+    let mut count = crate::COUNT;
+    let mut trials = crate::TRIALS;
+    let mut passes = None;
+    let mut warmup = crate::WARMUP;
+    let mut trial_time = crate::TRIAL_TIME;
+    let mut time_budget = None;
+    let mut timer = Timer::Instant;
+    let mut histogram = None;
+    let mut discard_interrupted = false;
+    let mut max_spread = crate::MAX_SPREAD;
+    let mut remeasure = crate::REMEASURE;
+    let mut estimator = Estimator::Min;
+    let mut stats = false;
+    let mut raw = false;
+    let mut format = report::Format::Text;
+    let mut chart = None;
+    let mut highlight = None;
+    let mut counters = false;
+    let mut cpu = None;
+    let mut nice = None;
+    let mut save_manifest = None;
+    let mut options = Vec::new();
+    'args: while let Some(arg) = args.pop_front() {
+        let from_manifest = args.len() >= command_line_len;
+        let (sources, benchmark) = if from_manifest {
+            (&mut manifest_sources, &mut manifest_benchmark)
+        } else {
+            (&mut sources, &mut benchmark)
+        };
         if let Some(arg) = arg.to_str() {
             if arg == "--unpredictable" {
                 unpredictable = true;
+                options.push(arg.to_owned());
+                continue;
+            }
+            if arg == "--distribution" {
+                let options = &mut sources.options;
+                let distribution = parse_with(&mut args, arg, options, Distribution::parse)?;
+                sources.set(Source::Random(distribution))?;
+                continue;
+            }
+            if arg == "--input" {
+                let path = PathBuf::from(value(&mut args, arg, &mut sources.options)?);
+                sources.set(Source::Input(path, Format::Text))?;
+                continue;
+            }
+            if arg == "--input-format" {
+                let options = &mut sources.options;
+                sources.input_format = Some(parse_with(&mut args, arg, options, Format::parse)?);
+                continue;
+            }
+            if arg == "--preset" {
+                let preset = parse_with(&mut args, arg, &mut sources.options, Preset::parse)?;
+                sources.set(Source::Preset(preset))?;
+                continue;
+            }
+            if arg == "--branch" {
+                let models = parse_with(&mut args, arg, &mut sources.options, Model::parse)?;
+                sources.set(Source::Branch(models))?;
+                continue;
+            }
+            if arg == "--monotonic" {
+                let value = value(&mut args, arg, &mut sources.options)?;
+                let (stride, jitter) = value.split_once(':').unwrap_or((&value, "0"));
+                let (Ok(stride), Ok(jitter)) = (stride.parse(), jitter.parse()) else {
+                    bail!("invalid value for {arg}: {value}");
+                };
+                if stride == 0 && jitter == 0 {
+                    bail!("invalid value for {arg}: {value}");
+                }
+                sources.set(Source::Monotonic { stride, jitter })?;
+                continue;
+            }
+            if arg == "--boundary" {
+                let k = parse_value(&mut args, arg)?;
+                sources.options.push(format!("{arg} {k}"));
+                sources.set(Source::Boundary(k))?;
+                continue;
+            }
+            if arg == "--lengths" {
+                lengths = parse_with(&mut args, arg, &mut options, Lengths::parse)?;
+                continue;
+            }
+            if arg == "--sweep" {
+                sweep = true;
+                options.push(arg.to_owned());
+                continue;
+            }
+            if arg == "--latency" {
+                latency = true;
+                options.push(arg.to_owned());
+                continue;
+            }
+            if arg == "--threads" {
+                threads = Some(value(&mut args, arg, &mut options)?);
+                continue;
+            }
+            if arg == "--interleave" {
+                interleave = true;
+                continue;
+            }
+            if arg == "--order-seed" {
+                order_seed = Some(parse_value(&mut args, arg)?);
+                continue;
+            }
+            if arg == "--seed" {
+                seed = parse_value(&mut args, arg)?;
+                continue;
+            }
+            if arg == "--count" {
+                count = parse_value(&mut args, arg)?;
+                continue;
+            }
+            if arg == "--trials" {
+                trials = parse_value(&mut args, arg)?;
+                continue;
+            }
+            if arg == "--passes" {
+                passes = Some(parse_value(&mut args, arg)?);
+                continue;
+            }
+            if arg == "--warmup" {
+                warmup = duration(&mut args, arg, 1e-3, &mut Vec::new())?;
+                continue;
+            }
+            if arg == "--trial-time" {
+                trial_time = duration(&mut args, arg, 1e-3, &mut Vec::new())?;
+                continue;
+            }
+            if arg == "--time-budget" {
+                time_budget = Some(duration(&mut args, arg, 1.0, &mut options)?);
+                continue;
+            }
+            if arg == "--timer" {
+                timer = parse_with(&mut args, arg, &mut options, Timer::parse)?;
+                continue;
+            }
+            if arg == "--histogram" {
+                let batch = parse_value(&mut args, arg)?;
+                if batch == 0 {
+                    bail!("invalid value for {arg}: {batch}");
+                }
+                options.push(format!("{arg} {batch}"));
+                histogram = Some(batch);
+                continue;
+            }
+            if arg == "--discard-interrupted" {
+                discard_interrupted = true;
+                options.push(arg.to_owned());
+                continue;
+            }
+            if arg == "--max-spread" {
+                let value = value(&mut args, arg, &mut options)?;
+                match value.parse::<f64>() {
+                    Ok(percent) if percent >= 0.0 => max_spread = percent / 100.0,
+                    _ => bail!("invalid value for {arg}: {value}"),
+                }
+                continue;
+            }
+            if arg == "--remeasure" {
+                remeasure = parse_value(&mut args, arg)?;
+                options.push(format!("{arg} {remeasure}"));
+                continue;
+            }
+            if arg == "--estimator" {
+                estimator = parse_with(&mut args, arg, &mut options, Estimator::parse)?;
+                continue;
+            }
+            if arg == "--stats" {
+                stats = true;
+                options.push(arg.to_owned());
+                continue;
+            }
+            if arg == "--raw" {
+                raw = true;
+                options.push(arg.to_owned());
+                continue;
+            }
+            if arg == "--format" {
+                format = parse_with(&mut args, arg, &mut Vec::new(), report::Format::parse)?;
+                continue;
+            }
+            if arg == "--chart" {
+                chart = Some(PathBuf::from(value(&mut args, arg, &mut Vec::new())?));
+                continue;
+            }
+            if arg == "--highlight" {
+                let name = value(&mut args, arg, &mut Vec::new())?;
+                match crate::IMPLS.iter().find(|imp| imp.name == name) {
+                    Some(imp) => highlight = Some(imp.name),
+                    None => bail!("unsupported: {name}"),
+                }
+                continue;
+            }
+            if arg == "--counters" {
+                counters = true;
+                options.push(arg.to_owned());
+                continue;
+            }
+            if arg == "--cpu" {
+                let n = parse_value(&mut args, arg)?;
+                options.push(format!("{arg} {n}"));
+                cpu = Some(n);
+                continue;
+            }
+            if arg == "--nice" {
+                let n = parse_value(&mut args, arg)?;
+                options.push(format!("{arg} {n}"));
+                nice = Some(n);
+                continue;
+            }
+            if arg == "--save-manifest" {
+                save_manifest = Some(PathBuf::from(value(&mut args, arg, &mut Vec::new())?));
                 continue;
             }
             let (lib, ty) = match arg.split_once(':') {
@@ -30,38 +361,18 @@ pub fn parse() -> Result<Args> {
             };
             for imp in crate::IMPLS {
                 if imp.name == lib {
+                    let mut types = imp.types();
                     match ty {
                         None => {
-                            if let Some(f) = imp.u32 {
-                                benchmark.push((imp.name, Type::U32(f)));
-                            }
-                            if let Some(f) = imp.u64 {
-                                benchmark.push((imp.name, Type::U64(f)));
-                            }
-                            if let Some(f) = imp.u128 {
-                                benchmark.push((imp.name, Type::U128(f)));
-                            }
+                            benchmark.extend(types.map(|f| (imp.name, f)));
                             continue 'args;
                         }
-                        Some("u32") => {
-                            if let Some(f) = imp.u32 {
-                                benchmark.push((imp.name, Type::U32(f)));
+                        Some(ty) => {
+                            if let Some(f) = types.find(|f| f.name() == ty) {
+                                benchmark.push((imp.name, f));
                                 continue 'args;
                             }
                         }
-                        Some("u64") => {
-                            if let Some(f) = imp.u64 {
-                                benchmark.push((imp.name, Type::U64(f)));
-                                continue 'args;
-                            }
-                        }
-                        Some("u128") => {
-                            if let Some(f) = imp.u128 {
-                                benchmark.push((imp.name, Type::U128(f)));
-                                continue 'args;
-                            }
-                        }
-                        Some(_) => {}
                     }
                 }
             }
@@ -69,22 +380,245 @@ pub fn parse() -> Result<Args> {
         bail!("unsupported: {}", arg.display());
     }
 
+    // Libraries or a data source given on the command line replace those of the
+    // manifest.
+    if benchmark.is_empty() {
+        benchmark = manifest_benchmark;
+    }
     if benchmark.is_empty() {
         for imp in crate::IMPLS {
-            if let Some(f) = imp.u32 {
-                benchmark.push((imp.name, Type::U32(f)));
-            }
-            if let Some(f) = imp.u64 {
-                benchmark.push((imp.name, Type::U64(f)));
-            }
-            if let Some(f) = imp.u128 {
-                benchmark.push((imp.name, Type::U128(f)));
-            }
+            benchmark.extend(imp.types().map(|f| (imp.name, f)));
         }
     }
+    if sources.source.is_none() {
+        sources.source = manifest_sources.source;
+        sources.input_format = sources.input_format.or(manifest_sources.input_format);
+        sources.options.splice(..0, manifest_sources.options);
+    }
+    let mut source = sources
+        .source
+        .unwrap_or(Source::Random(Distribution::PerLength));
+    if let Some(input_format) = sources.input_format {
+        let Source::Input(_path, format) = &mut source else {
+            bail!("--input-format requires --input");
+        };
+        *format = input_format;
+    }
+    if unpredictable
+        && let Source::Branch(_) | Source::Monotonic { .. } | Source::Boundary(_) = source
+    {
+        bail!("--unpredictable cannot be used together with --branch, --monotonic, --boundary");
+    }
+    if sweep && (unpredictable || matches!(source, Source::Branch(_))) {
+        bail!("--sweep cannot be used together with --unpredictable or --branch");
+    }
+    if threads.is_some()
+        && (sweep
+            || unpredictable
+            || counters
+            || histogram.is_some()
+            || matches!(source, Source::Branch(_)))
+    {
+        bail!(
+            "--threads cannot be used together with --sweep, --branch, --unpredictable, --counters or --histogram",
+        );
+    }
+    let threads = match &threads {
+        Some(spec) => system::placements(spec, cpu)?,
+        None => Vec::new(),
+    };
+    let order_seed =
+        (interleave || order_seed.is_some()).then(|| order_seed.unwrap_or_else(rand::random));
+    if order_seed.is_some() && (sweep || !threads.is_empty()) {
+        bail!("--interleave cannot be used together with --sweep or --threads");
+    }
+    if histogram.is_some() && !cfg!(target_arch = "x86_64") {
+        bail!("--histogram is only supported on x86_64");
+    }
+    let sampling = histogram.map(|batch| Sampling {
+        batch,
+        ticks_per_ns: f64::NAN,
+        overhead: 0,
+    });
+    if let Some(path) = &chart {
+        if path
+            .extension()
+            .is_none_or(|extension| extension != "tex" && extension != "svg")
+        {
+            bail!("unsupported chart format: {}", path.display());
+        }
+        if sweep || !threads.is_empty() {
+            bail!("--chart cannot be used together with --sweep or --threads");
+        }
+    } else if highlight.is_some() {
+        bail!("--highlight requires --chart");
+    }
+    if time_budget.is_some() && passes.is_some() {
+        bail!("--time-budget cannot be used together with --passes");
+    }
+    if trials == 0 {
+        bail!("--trials must be at least 1");
+    }
+    if passes == Some(0) {
+        bail!("--passes must be at least 1");
+    }
+    // Calibrated passes depend on the machine at the time, so a run saved to a
+    // manifest uses a fixed number of passes that a replay repeats.
+    if save_manifest.is_some() && passes.is_none() {
+        if time_budget.is_some() {
+            bail!("--time-budget cannot be used together with --save-manifest");
+        }
+        passes = Some(crate::PASSES);
+    }
+
+    let mut manifest = format!(
+        "# {} {}\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    for option in sources.options.iter().chain(&options) {
+        writeln!(manifest, "{option}").unwrap();
+    }
+    writeln!(manifest, "--seed {seed}").unwrap();
+    if let Some(order_seed) = order_seed {
+        writeln!(manifest, "--order-seed {order_seed}").unwrap();
+    }
+    writeln!(manifest, "--count {count}").unwrap();
+    writeln!(manifest, "--trials {trials}").unwrap();
+    if let Some(passes) = passes {
+        writeln!(manifest, "--passes {passes}").unwrap();
+    }
+    writeln!(manifest, "--warmup {}", warmup.as_secs_f64() * 1e3).unwrap();
+    writeln!(manifest, "--trial-time {}", trial_time.as_secs_f64() * 1e3).unwrap();
+    for (name, f) in &benchmark {
+        writeln!(manifest, "{name}:{}", f.name()).unwrap();
+    }
 
     Ok(Args {
         benchmark,
+        source,
+        source_options: sources.options,
         unpredictable,
+        lengths,
+        sweep,
+        latency,
+        threads,
+        order_seed,
+        seed,
+        count,
+        trials,
+        passes,
+        warmup,
+        trial_time,
+        time_budget,
+        timer,
+        sampling,
+        discard_interrupted,
+        max_spread,
+        remeasure,
+        estimator,
+        stats,
+        raw,
+        format,
+        chart,
+        highlight,
+        counters: if counters {
+            Some(Counters::open())
+        } else if let Timer::Tsc(_) = timer {
+            Some(Counters::cycles())
+        } else {
+            None
+        },
+        cpu,
+        nice,
+        save_manifest,
+        manifest,
     })
 }
+
+// Data source options from either the manifest or the command line.
+#[derive(Default)]
+struct Sources {
+    source: Option<Source>,
+    input_format: Option<Format>,
+    options: Vec<String>,
+}
+
+impl Args {
+    // Measures the time stamp counter for the tsc timer and --histogram. This is
+    // left out of parsing so that it runs on the thread's final CPU.
+    pub fn calibrate(&mut self) -> Result<()> {
+        self.timer = self.timer.calibrate()?;
+        if let Some(sampling) = &mut self.sampling {
+            let ticks_per_ns = match self.timer {
+                Timer::Tsc(ticks_per_ns) => ticks_per_ns,
+                Timer::Instant | Timer::ThreadCpu => timer::calibrate_tsc()?,
+            };
+            sampling.calibrate(ticks_per_ns);
+        }
+        Ok(())
+    }
+}
+
+impl Sources {
+    fn set(&mut self, source: Source) -> Result<()> {
+        if self.source.is_some() {
+            bail!(
+                "only one of --distribution, --input, --preset, --branch, --monotonic, --boundary may be given",
+            );
+        }
+        self.source = Some(source);
+        Ok(())
+    }
+}
+
+fn value(args: &mut VecDeque<OsString>, flag: &str, options: &mut Vec<String>) -> Result<String> {
+    match args.pop_front() {
+        Some(arg) => match arg.into_string() {
+            Ok(arg) => {
+                options.push(format!("{flag} {arg}"));
+                Ok(arg)
+            }
+            Err(arg) => bail!("unsupported: {}", arg.display()),
+        },
+        None => bail!("missing value for {flag}"),
+    }
+}
+
+fn parse_value<T>(args: &mut VecDeque<OsString>, flag: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value = value(args, flag, &mut Vec::new())?;
+    value
+        .parse()
+        .with_context(|| format!("invalid value for {flag}: {value}"))
+}
+
+// Parses the flag's value with the given function, naming the flag in errors.
+fn parse_with<T>(
+    args: &mut VecDeque<OsString>,
+    flag: &str,
+    options: &mut Vec<String>,
+    parse: impl FnOnce(&str) -> Result<T>,
+) -> Result<T> {
+    let value = value(args, flag, options)?;
+    parse(&value).with_context(|| format!("invalid value for {flag}: {value}"))
+}
+
+// Parses a number of the given unit, in seconds.
+fn duration(
+    args: &mut VecDeque<OsString>,
+    flag: &str,
+    unit: f64,
+    options: &mut Vec<String>,
+) -> Result<Duration> {
+    let value = value(args, flag, options)?;
+    if let Ok(n) = value.parse::<f64>()
+        && let Ok(duration) = Duration::try_from_secs_f64(n * unit)
+    {
+        return Ok(duration);
+    }
+    bail!("invalid value for {flag}: {value}");
+}
@@ -0,0 +1,124 @@
+use anyhow::{Context as _, Result, bail};
+use rand::RngExt as _;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom as _;
+
+const MARKOV: [f64; 7] = [0.001, 0.01, 0.03, 0.1, 0.3, 0.6, 0.9];
+const PERIOD: [usize; 5] = [1, 2, 3, 5, 8];
+
+// Number of values after which a periodic model picks a new pattern of random
+// lengths. A block holds only the lengths of its pattern, but over many blocks
+// every length is about equally represented.
+const BLOCK: usize = 1000;
+
+#[derive(Copy, Clone)]
+pub enum Model {
+    // Keep the previous value's length, or with probability p switch to a
+    // uniformly chosen different length.
+    Markov(f64),
+    // Repeat a fixed pattern of lengths with the given period.
+    Period(usize),
+}
+
+impl Model {
+    pub fn parse(arg: &str) -> Result<Vec<Self>> {
+        let (name, params) = match arg.split_once(':') {
+            Some((name, params)) => (name, Some(params)),
+            None => (arg, None),
+        };
+        let mut models = Vec::new();
+        match (name, params) {
+            ("markov", None) => models.extend(MARKOV.map(Model::Markov)),
+            ("period", None) => models.extend(PERIOD.map(Model::Period)),
+            ("markov", Some(params)) => {
+                for param in params.split(',') {
+                    let p: f64 = param
+                        .parse()
+                        .with_context(|| format!("invalid switch probability: {param}"))?;
+                    if !(0.0..=1.0).contains(&p) {
+                        bail!("invalid switch probability: {param}");
+                    }
+                    models.push(Model::Markov(p));
+                }
+            }
+            ("period", Some(params)) => {
+                for param in params.split(',') {
+                    let k: usize = param
+                        .parse()
+                        .with_context(|| format!("invalid period: {param}"))?;
+                    if k == 0 {
+                        bail!("invalid period: {param}");
+                    }
+                    models.push(Model::Period(k));
+                }
+            }
+            _ => bail!("unsupported branch model: {arg}"),
+        }
+        Ok(models)
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Model::Markov(p) => format!("markov:{p}"),
+            Model::Period(k) => format!("period:{k}"),
+        }
+    }
+
+    // Sequence of lengths drawn from the given choices.
+    pub fn lengths(self, rng: &mut SmallRng, choices: &[usize], count: usize) -> Vec<usize> {
+        let mut lengths = Vec::with_capacity(count);
+        match self {
+            Model::Markov(p) => {
+                let mut i = rng.random_range(0..choices.len());
+                for _ in 0..count {
+                    if choices.len() > 1 && rng.random_bool(p) {
+                        let other = rng.random_range(0..choices.len() - 1);
+                        i = if other < i { other } else { other + 1 };
+                    }
+                    lengths.push(choices[i]);
+                }
+            }
+            Model::Period(k) => {
+                let mut pattern = choices.to_vec();
+                while lengths.len() < count {
+                    pattern.shuffle(rng);
+                    let block = Ord::min(BLOCK, count - lengths.len());
+                    lengths.extend(pattern.iter().cycle().take(k).cycle().take(block));
+                }
+            }
+        }
+        lengths
+    }
+
+    // Conditional entropy in bits of each length given the previous one. For
+    // periodic models this is computed separately within each block that
+    // shares a pattern, and averaged.
+    pub fn entropy(self, lengths: &[usize], max_length: usize) -> f64 {
+        let block = match self {
+            Model::Markov(_) => Ord::max(lengths.len(), 1),
+            Model::Period(_) => BLOCK,
+        };
+        let mut entropy = 0.0;
+        let mut total = 0usize;
+        for chunk in lengths.chunks(block) {
+            let mut pairs = vec![0usize; max_length * max_length];
+            let mut prev = vec![0usize; max_length];
+            for window in chunk.windows(2) {
+                pairs[(window[0] - 1) * max_length + window[1] - 1] += 1;
+                prev[window[0] - 1] += 1;
+                total += 1;
+            }
+            for (i, &n) in pairs.iter().enumerate() {
+                if n != 0 {
+                    let n = n as f64;
+                    entropy -= n * (n / prev[i / max_length] as f64).log2();
+                }
+            }
+        }
+        if total == 0 {
+            0.0
+        } else {
+            entropy / total as f64
+        }
+    }
+}
@@ -0,0 +1,464 @@
+use std::fmt::Write as _;
+
+// Types that get an axis each, side by side.
+pub const TYPES: [&str; 3] = ["u32", "u64", "u128"];
+
+// Nanoseconds per value of one implementation at one digit length. The y axis
+// starts at 0, so negative results are drawn at 0.
+pub struct Entry {
+    pub name: &'static str,
+    pub ty: &'static str,
+    pub length: usize,
+    pub ns: f64,
+}
+
+// The pgfplots document behind performance.png, with one axis per type that
+// has results and a legend entry per implementation.
+pub fn tex(entries: &[Entry], highlight: Option<&str>) -> String {
+    let names = names(entries);
+    let types: Vec<&str> = TYPES
+        .into_iter()
+        .filter(|ty| entries.iter().any(|entry| entry.ty == *ty))
+        .collect();
+    let ymax = entries.iter().map(|entry| entry.ns).fold(0.0, f64::max) * 1.05;
+    let ymax = if ymax > 0.0 { ymax } else { 1.0 };
+
+    let mut out = String::new();
+    out.push_str(concat!(
+        "\\documentclass{standalone}\n",
+        "\\usepackage{pgfplots}\n",
+        "\\usepackage{sansmath}\n",
+        "\\pgfplotsset{compat=1.16}\n",
+        "\\definecolor{bg}{HTML}{D8D8D8}\n",
+    ));
+    for name in &names {
+        writeln!(
+            out,
+            "\\definecolor{{{}}}{{HTML}}{{{}}}",
+            color_name(name),
+            color(name)
+        )
+        .unwrap();
+    }
+    out.push_str(concat!(
+        "\\tikzset{\n",
+        "  on layer/.code={\n",
+        "    \\pgfonlayer{#1}\\begingroup\n",
+        "    \\aftergroup\\endpgfonlayer\n",
+        "    \\aftergroup\\endgroup\n",
+        "  },\n",
+        "}\n",
+        "\\pgfplotsset{\n",
+        "  every axis/.append style={\n",
+        "    x=5pt,\n",
+        "    height=3.5in,\n",
+        "    xmin=0.1,\n",
+        "    ymin=0,\n",
+    ));
+    writeln!(out, "    ymax={ymax:.1},").unwrap();
+    out.push_str(concat!(
+        "    xtick pos=bottom,\n",
+        "    xtick distance=5,\n",
+        "    xticklabel shift=-1pt,\n",
+        "    tick label style={font=\\sansmath\\sffamily},\n",
+        "    every axis label={font=\\sansmath\\sffamily},\n",
+        "    label style={font=\\sansmath\\sffamily},\n",
+        "    ymajorgrids=true,\n",
+        "    major grid style={line width=0.8pt,draw=gray!55},\n",
+        "    axis background/.style={fill=bg},\n",
+        "  },\n",
+        "  every axis plot/.append style={\n",
+        "    line width=0.8pt,\n",
+        "    mark=*,\n",
+        "    forget plot,\n",
+        "  },\n",
+        "  area legend/.style={\n",
+        "    legend image code/.code={\n",
+        "      \\draw[#1] (0cm,-0.1cm) rectangle (0.35cm,0.1cm);\n",
+        "    },\n",
+        "  },\n",
+        "  highlight/.style={\n",
+        "    preaction={\n",
+        "      on layer=pre main,\n",
+        "      line width=6pt,\n",
+        "      opacity=0.5,\n",
+        "      line cap=round,\n",
+        "      line join=round,\n",
+        "      yellow,\n",
+        "    },\n",
+        "  },\n",
+        "}\n",
+        "\\begin{document}\n",
+        "\\pagecolor{white}\n",
+        "\\begin{tikzpicture}[\n",
+        "  every mark/.append style={mark size=1pt},\n",
+        "]\n",
+    ));
+    for (i, ty) in types.iter().enumerate() {
+        out.push_str("\\begin{axis}[\n");
+        writeln!(out, "  name={ty},").unwrap();
+        if i > 0 {
+            writeln!(out, "  at=({}.east),", types[i - 1]).unwrap();
+            out.push_str("  anchor=west,\n");
+            writeln!(out, "  xshift={}pt,", 3 * i).unwrap();
+        }
+        writeln!(out, "  xmax={}.9,", max_length(entries, ty)).unwrap();
+        writeln!(out, "  xlabel={{digits in {ty}}},").unwrap();
+        if i == 0 {
+            out.push_str(concat!(
+                "  ylabel={duration\\enskip(\\kern-1pt nanoseconds\\kern-1pt)},\n",
+                "  yticklabel shift=-1.25pt,\n",
+            ));
+        } else {
+            out.push_str("  yticklabel=\\empty,\n");
+        }
+        out.push_str("  set layers,\n");
+        if i == 0 {
+            out.push_str(concat!(
+                "  legend style={anchor=north west, at={(0.1,0.975)}, font=\\sansmath\\sffamily},\n",
+                "  legend cell align=left,\n",
+            ));
+        }
+        out.push_str("]\n");
+        for name in &names {
+            let color = color_name(name);
+            if i == 0 {
+                writeln!(out, "  \\addlegendentry{{{}}}", escape(description(name))).unwrap();
+                writeln!(
+                    out,
+                    "  \\addlegendimage{{color={color}, fill, area legend}}"
+                )
+                .unwrap();
+            }
+            let points: Vec<&Entry> = entries
+                .iter()
+                .filter(|entry| entry.name == *name && entry.ty == *ty)
+                .collect();
+            if points.is_empty() {
+                continue;
+            }
+            let style = if highlight == Some(*name) {
+                ", highlight"
+            } else {
+                ""
+            };
+            writeln!(out, "  \\addplot[color={color}{style}] coordinates {{").unwrap();
+            for entry in points {
+                let ns = f64::max(entry.ns, 0.0);
+                writeln!(out, "    ({}, {ns:.2})", entry.length).unwrap();
+            }
+            out.push_str("  };\n");
+        }
+        out.push_str("\\end{axis}\n");
+    }
+    if let (Some(first), Some(last)) = (types.first(), types.last()) {
+        writeln!(out, "\\pgfresetboundingbox\\path").unwrap();
+        writeln!(out, "  ({first}.south west) -- ++(-0.4in,-0.39in)").unwrap();
+        writeln!(out, "  rectangle ({last}.north east) -- ++(9pt,3pt);").unwrap();
+    }
+    out.push_str("\\end{tikzpicture}\n\\end{document}\n");
+    out
+}
+
+// Implementations in the order they were first measured.
+fn names(entries: &[Entry]) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    for entry in entries {
+        if !names.contains(&entry.name) {
+            names.push(entry.name);
+        }
+    }
+    names
+}
+
+fn max_length(entries: &[Entry], ty: &str) -> usize {
+    entries
+        .iter()
+        .filter(|entry| entry.ty == ty)
+        .map(|entry| entry.length)
+        .max()
+        .unwrap_or(0)
+}
+
+fn description(name: &str) -> &'static str {
+    crate::IMPLS
+        .iter()
+        .find(|imp| imp.name == name)
+        .map_or("", |imp| imp.description)
+}
+
+fn color(name: &str) -> &'static str {
+    crate::IMPLS
+        .iter()
+        .find(|imp| imp.name == name)
+        .map_or("000000", |imp| imp.color)
+}
+
+// Implementation names like `to-arraystring` are not all valid as TeX color
+// names.
+fn color_name(name: &str) -> String {
+    name.chars().filter(char::is_ascii_alphanumeric).collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for ch in text.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '"' => escaped.push_str("\\textquotedbl{}"),
+            '&' | '_' | '{' | '}' | '#' | '$' | '%' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+// Pixels per digit on the x axis, and the height of the plot area.
+const SVG_DIGIT: f64 = 12.0;
+const SVG_HEIGHT: f64 = 336.0;
+// Space around the panels for tick labels and axis labels.
+const SVG_LEFT: f64 = 56.0;
+const SVG_BOTTOM: f64 = 52.0;
+const SVG_MARGIN: f64 = 10.0;
+const SVG_FONT: f64 = 14.0;
+
+// The same layout as the pgfplots document, drawn directly as SVG so that no
+// TeX installation is needed to view it.
+pub fn svg(entries: &[Entry], highlight: Option<&str>) -> String {
+    let names = names(entries);
+    let types: Vec<&str> = TYPES
+        .into_iter()
+        .filter(|ty| entries.iter().any(|entry| entry.ty == *ty))
+        .collect();
+    let ymax = entries.iter().map(|entry| entry.ns).fold(0.0, f64::max) * 1.05;
+    let ymax = if ymax > 0.0 { ymax } else { 1.0 };
+    let step = tick_step(ymax);
+    let y = |ns: f64| SVG_MARGIN + SVG_HEIGHT * (1.0 - ns / ymax);
+
+    let mut panels = Vec::new();
+    let mut left = SVG_LEFT;
+    for (i, ty) in types.iter().enumerate() {
+        if i > 0 {
+            left += 4.0 * i as f64;
+        }
+        let max_length = max_length(entries, ty);
+        let width = (max_length as f64 + 0.8) * SVG_DIGIT;
+        panels.push((*ty, left, width, max_length));
+        left += width;
+    }
+    let width = left + SVG_MARGIN;
+    let height = SVG_MARGIN + SVG_HEIGHT + SVG_BOTTOM;
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.0}\" height=\"{height:.0}\" viewBox=\"0 0 {width:.0} {height:.0}\" font-family=\"sans-serif\" font-size=\"{SVG_FONT}\">",
+    )
+    .unwrap();
+    writeln!(out, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>").unwrap();
+    let bottom = SVG_MARGIN + SVG_HEIGHT;
+    for &(ty, left, width, max_length) in &panels {
+        let x = |length: usize| left + (length as f64 - 0.1) * SVG_DIGIT;
+        writeln!(
+            out,
+            "<rect x=\"{left:.1}\" y=\"{SVG_MARGIN}\" width=\"{width:.1}\" height=\"{SVG_HEIGHT}\" fill=\"#D8D8D8\"/>",
+        )
+        .unwrap();
+        for tick in ticks(ymax, step).skip(1) {
+            writeln!(
+                out,
+                "<line x1=\"{left:.1}\" y1=\"{0:.1}\" x2=\"{1:.1}\" y2=\"{0:.1}\" stroke=\"#8C8C8C\" stroke-width=\"1\"/>",
+                y(tick),
+                left + width,
+            )
+            .unwrap();
+        }
+        for length in (5..=max_length).step_by(5) {
+            writeln!(
+                out,
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{length}</text>",
+                x(length),
+                bottom + SVG_FONT + 4.0,
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">digits in {ty}</text>",
+            left + width / 2.0,
+            bottom + 2.0 * SVG_FONT + 14.0,
+        )
+        .unwrap();
+        let mut series = Vec::new();
+        for name in &names {
+            let mut points = String::new();
+            for entry in entries
+                .iter()
+                .filter(|entry| entry.name == *name && entry.ty == ty)
+            {
+                if !points.is_empty() {
+                    points.push(' ');
+                }
+                let ns = f64::max(entry.ns, 0.0);
+                write!(points, "{:.1},{:.1}", x(entry.length), y(ns)).unwrap();
+            }
+            if !points.is_empty() {
+                series.push((*name, points));
+            }
+        }
+        // Behind every line, like the pre main layer in pgfplots.
+        for (_name, points) in series.iter().filter(|(name, _)| highlight == Some(*name)) {
+            writeln!(
+                out,
+                "<polyline points=\"{points}\" fill=\"none\" stroke=\"yellow\" stroke-opacity=\"0.5\" stroke-width=\"8\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>",
+            )
+            .unwrap();
+        }
+        for (name, points) in &series {
+            let color = color(name);
+            writeln!(
+                out,
+                "<polyline points=\"{points}\" fill=\"none\" stroke=\"#{color}\" stroke-width=\"1.5\"/>",
+            )
+            .unwrap();
+            for point in points.split(' ') {
+                let (cx, cy) = point.split_once(',').unwrap();
+                writeln!(
+                    out,
+                    "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"2\" fill=\"#{color}\"/>",
+                )
+                .unwrap();
+            }
+        }
+        writeln!(
+            out,
+            "<rect x=\"{left:.1}\" y=\"{SVG_MARGIN}\" width=\"{width:.1}\" height=\"{SVG_HEIGHT}\" fill=\"none\" stroke=\"black\"/>",
+        )
+        .unwrap();
+    }
+
+    // Enough decimals to tell the ticks apart.
+    let decimals = (-step.log10()).ceil().max(0.0) as usize;
+    for tick in ticks(ymax, step) {
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{tick:.decimals$}</text>",
+            SVG_LEFT - 4.0,
+            y(tick) + SVG_FONT / 3.0,
+        )
+        .unwrap();
+    }
+    writeln!(
+        out,
+        "<text transform=\"translate({:.1},{:.1}) rotate(-90)\" text-anchor=\"middle\">duration (nanoseconds)</text>",
+        SVG_FONT,
+        SVG_MARGIN + SVG_HEIGHT / 2.0,
+    )
+    .unwrap();
+
+    if let Some(&(_ty, left, width, _max_length)) = panels.first() {
+        let row = SVG_FONT + 6.0;
+        let longest = names
+            .iter()
+            .map(|name| description(name).chars().count())
+            .max()
+            .unwrap_or(0);
+        // Text width estimated at 0.6 em per character.
+        let legend_width = 30.0 + longest as f64 * SVG_FONT * 0.6;
+        let (lx, ly) = (left + 0.1 * width, SVG_MARGIN + 8.0);
+        writeln!(
+            out,
+            "<rect x=\"{lx:.1}\" y=\"{ly:.1}\" width=\"{legend_width:.1}\" height=\"{:.1}\" fill=\"white\" stroke=\"black\"/>",
+            names.len() as f64 * row + 6.0,
+        )
+        .unwrap();
+        for (i, name) in names.iter().enumerate() {
+            let top = ly + 6.0 + i as f64 * row;
+            writeln!(
+                out,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"16\" height=\"{:.1}\" fill=\"#{}\"/>",
+                lx + 6.0,
+                top + 3.0,
+                row - 8.0,
+                color(name),
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+                lx + 26.0,
+                top + SVG_FONT,
+                escape_xml(description(name)),
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+// Distance between labeled values on the y axis: 1, 2 or 5 times a power of
+// ten, giving about four to eight ticks.
+fn tick_step(ymax: f64) -> f64 {
+    let magnitude = 10f64.powf((ymax / 4.0).log10().floor());
+    for factor in [1.0, 2.0, 5.0] {
+        if ymax / (factor * magnitude) <= 8.0 {
+            return factor * magnitude;
+        }
+    }
+    10.0 * magnitude
+}
+
+fn ticks(ymax: f64, step: f64) -> impl Iterator<Item = f64> {
+    (0..)
+        .map(move |i| f64::from(i) * step)
+        .take_while(move |&tick| tick < ymax)
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::new();
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::{escape, escape_xml, tick_step, ticks};
+
+    #[test]
+    fn test_tick_step() {
+        assert_eq!(tick_step(8.0), 1.0);
+        assert_eq!(tick_step(10.0), 2.0);
+        assert_eq!(tick_step(39.0), 5.0);
+        assert_eq!(tick_step(41.0), 10.0);
+        assert_eq!(tick_step(100.0), 20.0);
+        assert_eq!(tick_step(3.0), 0.5);
+        let ticks: Vec<f64> = ticks(10.0, 2.0).collect();
+        assert_eq!(ticks, [0.0, 2.0, 4.0, 6.0, 8.0]);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("itoa"), "itoa");
+        assert_eq!(escape("a_b & 50%"), "a\\_b \\& 50\\%");
+        assert_eq!(
+            escape("\\\"~^{#$}"),
+            "\\textbackslash{}\\textquotedbl{}\\textasciitilde{}\\textasciicircum{}\\{\\#\\$\\}",
+        );
+        assert_eq!(escape_xml("a<b> & \"c\""), "a&lt;b&gt; &amp; &quot;c&quot;");
+    }
+}
@@ -0,0 +1,235 @@
+use std::fmt::{self, Display};
+use std::fs::File;
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_TYPE_SOFTWARE: u32 = 1;
+const PERF_TYPE_HW_CACHE: u32 = 3;
+
+const HARDWARE: [(&str, u32, u64); 5] = [
+    ("instructions", PERF_TYPE_HARDWARE, 1),
+    ("branches", PERF_TYPE_HARDWARE, 4),
+    ("branch-misses", PERF_TYPE_HARDWARE, 5),
+    // Cache L1D, operation read, result miss.
+    ("l1d-misses", PERF_TYPE_HW_CACHE, 1 << 16),
+    ("cycles", PERF_TYPE_HARDWARE, 0),
+];
+
+const SOFTWARE: [(&str, u32, u64); 3] = [
+    ("task-clock", PERF_TYPE_SOFTWARE, 1),
+    ("page-faults", PERF_TYPE_SOFTWARE, 2),
+    ("context-switches", PERF_TYPE_SOFTWARE, 3),
+];
+
+pub struct Counters {
+    events: Vec<(&'static str, Option<File>)>,
+}
+
+#[derive(Clone)]
+pub struct Counts {
+    values: Vec<(&'static str, Option<f64>)>,
+}
+
+impl Counters {
+    // Counters of the calling thread. Events that the kernel or container does
+    // not permit are reported as unavailable; if no hardware event is
+    // available, software events are counted instead.
+    pub fn open() -> Self {
+        let mut events: Vec<_> = HARDWARE
+            .iter()
+            .map(|&(name, kind, config)| (name, sys::open(kind, config)))
+            .collect();
+        if events.iter().all(|(_name, file)| file.is_none()) {
+            eprintln!("warning: hardware performance counters are unavailable");
+            events.extend(
+                SOFTWARE
+                    .iter()
+                    .map(|&(name, kind, config)| (name, sys::open(kind, config))),
+            );
+        }
+        Counters { events }
+    }
+
+    // Only the core cycles, which the time stamp counter does not count since
+    // it ticks at a constant rate regardless of the core's clock.
+    pub fn cycles() -> Self {
+        let events: Vec<_> = HARDWARE
+            .iter()
+            .filter(|&&(name, _kind, _config)| name == "cycles")
+            .map(|&(name, kind, config)| (name, sys::open(kind, config)))
+            .collect();
+        if events.iter().all(|(_name, file)| file.is_none()) {
+            eprintln!("warning: the cycles performance counter is unavailable");
+        }
+        Counters { events }
+    }
+
+    pub fn start(&self) {
+        for file in self.events.iter().filter_map(|(_name, file)| file.as_ref()) {
+            sys::reset(file);
+            sys::enable(file);
+        }
+    }
+
+    pub fn stop(&self) -> Counts {
+        for file in self.events.iter().filter_map(|(_name, file)| file.as_ref()) {
+            sys::disable(file);
+        }
+        let values = self
+            .events
+            .iter()
+            .map(|(name, file)| (*name, file.as_ref().and_then(sys::read)))
+            .collect();
+        Counts { values }
+    }
+}
+
+impl Counts {
+    pub fn values(&self) -> &[(&'static str, Option<f64>)] {
+        &self.values
+    }
+
+    pub fn per(mut self, n: f64) -> Self {
+        for (_name, value) in &mut self.values {
+            *value = value.map(|value| value / n);
+        }
+        self
+    }
+
+    pub fn plus(mut self, other: &Counts) -> Self {
+        for ((_name, value), (_, other)) in self.values.iter_mut().zip(&other.values) {
+            *value = value.zip(*other).map(|(value, other)| value + other);
+        }
+        self
+    }
+
+    pub fn minus(mut self, baseline: &Counts) -> Self {
+        for ((_name, value), (_, base)) in self.values.iter_mut().zip(&baseline.values) {
+            *value = value
+                .zip(*base)
+                .map(|(value, base)| f64::max(value - base, 0.0));
+        }
+        self
+    }
+}
+
+impl Display for Counts {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (name, value)) in self.values.iter().enumerate() {
+            if i > 0 {
+                formatter.write_str(" ")?;
+            }
+            match value {
+                Some(value) => write!(formatter, "{name} {value:.3}")?,
+                None => write!(formatter, "{name} unavailable")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::fs::File;
+    use std::io::Read as _;
+    use std::mem;
+    use std::os::fd::{AsRawFd as _, FromRawFd as _};
+
+    const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+    const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+    const DISABLED: u64 = 1 << 0;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+    const PERF_EVENT_IOC_ENABLE: libc::Ioctl = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: libc::Ioctl = 0x2401;
+    const PERF_EVENT_IOC_RESET: libc::Ioctl = 0x2403;
+    const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+
+    // The fields of struct perf_event_attr up to PERF_ATTR_SIZE_VER0.
+    #[repr(C)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    pub fn open(kind: u32, config: u64) -> Option<File> {
+        let attr = PerfEventAttr {
+            kind,
+            size: mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            sample_period: 0,
+            sample_type: 0,
+            read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+            flags: DISABLED | EXCLUDE_KERNEL | EXCLUDE_HV,
+            wakeup_events: 0,
+            bp_type: 0,
+            config1: 0,
+        };
+        let (pid, cpu, group_fd, flags) = (0, -1, -1, PERF_FLAG_FD_CLOEXEC);
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &raw const attr,
+                pid,
+                cpu,
+                group_fd,
+                flags,
+            )
+        };
+        if fd < 0 {
+            return None;
+        }
+        Some(unsafe { File::from_raw_fd(fd as i32) })
+    }
+
+    pub fn enable(file: &File) {
+        unsafe { libc::ioctl(file.as_raw_fd(), PERF_EVENT_IOC_ENABLE, 0) };
+    }
+
+    pub fn disable(file: &File) {
+        unsafe { libc::ioctl(file.as_raw_fd(), PERF_EVENT_IOC_DISABLE, 0) };
+    }
+
+    pub fn reset(file: &File) {
+        unsafe { libc::ioctl(file.as_raw_fd(), PERF_EVENT_IOC_RESET, 0) };
+    }
+
+    // Count scaled up for the time the event was not scheduled on a counter
+    // because more events were open than the hardware has counters.
+    pub fn read(mut file: &File) -> Option<f64> {
+        let mut buf = [0u8; 24];
+        file.read_exact(&mut buf).ok()?;
+        let [value, enabled, running] =
+            [0, 8, 16].map(|i| u64::from_ne_bytes(buf[i..i + 8].try_into().unwrap()));
+        if running == 0 {
+            return None;
+        }
+        Some(value as f64 * enabled as f64 / running as f64)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::fs::File;
+
+    pub fn open(_kind: u32, _config: u64) -> Option<File> {
+        None
+    }
+
+    pub fn enable(_file: &File) {}
+
+    pub fn disable(_file: &File) {}
+
+    pub fn reset(_file: &File) {}
+
+    pub fn read(_file: &File) -> Option<f64> {
+        None
+    }
+}
@@ -1,87 +1,680 @@
-use crate::Unsigned;
-use rand::SeedableRng as _;
+use crate::Integer;
+use crate::branch::Model;
+use crate::distribution::Distribution;
+use crate::input::{self, Format, Value};
+use crate::preset::Preset;
+use anyhow::{Context as _, Result, bail};
+use rand::distr::uniform::SampleUniform;
 use rand::distr::{Distribution as _, Uniform};
 use rand::rngs::SmallRng;
 use rand::seq::SliceRandom as _;
+use rand::{Rng, RngExt as _, SeedableRng as _};
+use std::any;
 use std::array;
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+pub enum Source {
+    Random(Distribution),
+    Input(PathBuf, Format),
+    Preset(Preset),
+    Branch(Vec<Model>),
+    Monotonic { stride: u128, jitter: u128 },
+    Boundary(u128),
+}
+
+pub struct Lengths {
+    ranges: Vec<RangeInclusive<usize>>,
+}
+
+// Rand does not sample usize and isize, whose range varies by platform, so data
+// for them is generated using the fixed-size type of the same range.
+#[cfg(target_pointer_width = "16")]
+type Usize = u16;
+#[cfg(target_pointer_width = "32")]
+type Usize = u32;
+#[cfg(target_pointer_width = "64")]
+type Usize = u64;
+#[cfg(target_pointer_width = "16")]
+type Isize = i16;
+#[cfg(target_pointer_width = "32")]
+type Isize = i32;
+#[cfg(target_pointer_width = "64")]
+type Isize = i64;
+
+const USIZE_LENGTH: usize = usize::MAX.ilog10() as usize + 1;
+const ISIZE_LENGTH: usize = isize::MIN.unsigned_abs().ilog10() as usize + 2;
+
+// Number of draws per wanted value after which sampling a distribution gives up
+// on values of the selected lengths.
+const ATTEMPTS: usize = 100;
+
+pub const TYPES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
 
 pub struct Data {
-    pub u32: DataForType<u32, 10>,
-    pub u64: DataForType<u64, 20>,
-    pub u128: DataForType<u128, 39>,
+    pub u8: Option<DataForType<u8, 3>>,
+    pub u16: Option<DataForType<u16, 5>>,
+    pub u32: Option<DataForType<u32, 10>>,
+    pub u64: Option<DataForType<u64, 20>>,
+    pub u128: Option<DataForType<u128, 39>>,
+    pub usize: Option<DataForType<usize, USIZE_LENGTH>>,
+    pub i8: Option<DataForType<i8, 4>>,
+    pub i16: Option<DataForType<i16, 6>>,
+    pub i32: Option<DataForType<i32, 11>>,
+    pub i64: Option<DataForType<i64, 20>>,
+    pub i128: Option<DataForType<i128, 40>>,
+    pub isize: Option<DataForType<isize, ISIZE_LENGTH>>,
+}
+
+// Options that the data of every type is generated from.
+struct Params<'a> {
+    source: &'a Source,
+    values: &'a [Value],
+    lengths: &'a Lengths,
+    count: usize,
+    unpredictable: bool,
 }
 
 pub struct DataForType<T, const N: usize> {
-    pub count: usize,
     pub mixed: Vec<T>,
     pub by_length: [Vec<T>; N],
     pub unpredictable: bool,
+    pub sequences: Vec<Sequence<T>>,
+}
+
+// Uniform over all values whose decimal representation has a given length,
+// which for signed types includes negative values one digit shorter.
+pub struct UniformForLength<T>
+where
+    T: SampleUniform,
+{
+    nonnegative: Option<Uniform<T>>,
+    negative: Option<Uniform<T>>,
+    negative_weight: f64,
+    // Number of values of the length.
+    pub size: f64,
+}
+
+pub struct Sequence<T> {
+    pub label: String,
+    pub entropy: f64,
+    pub values: Vec<T>,
 }
 
 impl Data {
-    pub fn random(count: usize, unpredictable: bool) -> Self {
-        let mut rng = SmallRng::seed_from_u64(1);
-        Data {
-            u32: DataForType::random(&mut rng, count, unpredictable),
-            u64: DataForType::random(&mut rng, count, unpredictable),
-            u128: DataForType::random(&mut rng, count, unpredictable),
+    pub fn new(
+        source: &Source,
+        lengths: &Lengths,
+        count: usize,
+        seed: u64,
+        unpredictable: bool,
+        types: &[&str],
+    ) -> Result<Self> {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let values = match source {
+            Source::Input(path, format) => input::load(path, *format)?,
+            Source::Preset(preset) => {
+                let values = preset.generate(&mut rng, count);
+                values.into_iter().map(Value::Unsigned).collect()
+            }
+            _ => Vec::new(),
+        };
+        // Every type draws its data from a stream of its own, so that its data
+        // does not depend on which other types are measured.
+        let [
+            u8,
+            u16,
+            u32,
+            u64,
+            u128,
+            usize,
+            i8,
+            i16,
+            i32,
+            i64,
+            i128,
+            isize,
+        ] = TYPES.map(|ty| {
+            let seed = rng.random();
+            types.contains(&ty).then(|| SmallRng::seed_from_u64(seed))
+        });
+        let params = Params {
+            source,
+            values: &values,
+            lengths,
+            count,
+            unpredictable,
+        };
+        let data = Data {
+            u8: u8.map(|rng| DataForType::new(rng, &params)),
+            u16: u16.map(|rng| DataForType::new(rng, &params)),
+            u32: u32.map(|rng| DataForType::new(rng, &params)),
+            u64: u64.map(|rng| DataForType::new(rng, &params)),
+            u128: u128.map(|rng| DataForType::new(rng, &params)),
+            usize: usize.map(|rng| {
+                DataForType::<Usize, USIZE_LENGTH>::new(rng, &params).map(|n| n as usize)
+            }),
+            i8: i8.map(|rng| DataForType::new(rng, &params)),
+            i16: i16.map(|rng| DataForType::new(rng, &params)),
+            i32: i32.map(|rng| DataForType::new(rng, &params)),
+            i64: i64.map(|rng| DataForType::new(rng, &params)),
+            i128: i128.map(|rng| DataForType::new(rng, &params)),
+            isize: isize.map(|rng| {
+                DataForType::<Isize, ISIZE_LENGTH>::new(rng, &params).map(|n| n as isize)
+            }),
+        };
+        if let Source::Input(..) | Source::Preset(_) = source {
+            data.report();
         }
+        Ok(data)
+    }
+
+    fn report(&self) {
+        self.u8.iter().for_each(DataForType::report);
+        self.u16.iter().for_each(DataForType::report);
+        self.u32.iter().for_each(DataForType::report);
+        self.u64.iter().for_each(DataForType::report);
+        self.u128.iter().for_each(DataForType::report);
+        self.usize.iter().for_each(DataForType::report);
+        self.i8.iter().for_each(DataForType::report);
+        self.i16.iter().for_each(DataForType::report);
+        self.i32.iter().for_each(DataForType::report);
+        self.i64.iter().for_each(DataForType::report);
+        self.i128.iter().for_each(DataForType::report);
+        self.isize.iter().for_each(DataForType::report);
+    }
+}
+
+impl Lengths {
+    pub fn all() -> Self {
+        Lengths {
+            ranges: vec![1..=usize::MAX],
+        }
+    }
+
+    // Comma-separated lengths and ranges, like "1..=8" or "1,2,10..".
+    pub fn parse(arg: &str) -> Result<Self> {
+        let mut ranges = Vec::new();
+        for item in arg.split(',') {
+            let bound = |n: &str, default: usize| -> Result<usize> {
+                if n.is_empty() {
+                    Ok(default)
+                } else {
+                    n.parse().with_context(|| format!("invalid length: {n}"))
+                }
+            };
+            let range = if let Some((lo, hi)) = item.split_once("..=") {
+                bound(lo, 1)?..=bound(hi, usize::MAX)?
+            } else if let Some((lo, hi)) = item.split_once("..") {
+                bound(lo, 1)?..=bound(hi, usize::MAX)?.saturating_sub(usize::from(!hi.is_empty()))
+            } else {
+                let n = bound(item, 0)?;
+                n..=n
+            };
+            if *range.start() == 0 || range.is_empty() {
+                bail!("invalid lengths: {item}");
+            }
+            ranges.push(range);
+        }
+        Ok(Lengths { ranges })
+    }
+
+    pub fn contains(&self, len: usize) -> bool {
+        self.ranges.iter().any(|range| range.contains(&len))
+    }
+
+    // Selected lengths from 1 to max_length, in increasing order.
+    pub fn within(&self, max_length: usize) -> Vec<usize> {
+        (1..=max_length).filter(|&len| self.contains(len)).collect()
+    }
+}
+
+impl Display for Lengths {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, range) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                formatter.write_str(",")?;
+            }
+            match (*range.start(), *range.end()) {
+                (lo, hi) if lo == hi => write!(formatter, "{lo}")?,
+                (lo, usize::MAX) => write!(formatter, "{lo}..")?,
+                (lo, hi) => write!(formatter, "{lo}..={hi}")?,
+            }
+        }
+        Ok(())
     }
 }
 
 impl<T, const N: usize> DataForType<T, N>
 where
-    T: Unsigned,
+    T: Integer + SampleUniform,
 {
-    fn random(rng: &mut SmallRng, count: usize, unpredictable: bool) -> Self {
-        let mut mixed = Vec::new();
-        let mut by_length = [const { Vec::new() }; N];
+    fn new(mut rng: SmallRng, params: &Params) -> Self {
+        let Params {
+            source,
+            values,
+            lengths,
+            count,
+            unpredictable,
+        } = *params;
+        let rng = &mut rng;
+        let mut data = match source {
+            Source::Random(distribution) => {
+                DataForType::random(rng, distribution, lengths, count, unpredictable)
+            }
+            Source::Input(..) | Source::Preset(_) => {
+                DataForType::from_values(values, lengths, unpredictable)
+            }
+            Source::Branch(models) => DataForType::branch(rng, models, lengths, count),
+            Source::Monotonic { stride, jitter } => {
+                DataForType::monotonic(rng, *stride, *jitter, lengths, count)
+            }
+            Source::Boundary(k) => DataForType::boundary(rng, *k, lengths, count),
+        };
         if unpredictable {
-            let mixed_distr: [Uniform<T>; N] =
-                array::from_fn(|i| uniform_distribution_for_length(i + 1));
-            mixed.reserve_exact(count);
-            for i in 0..count {
-                mixed.push(mixed_distr[i % N].sample(rng));
+            data.mix_into_lengths(rng, count);
+        }
+        data
+    }
+
+    fn random(
+        rng: &mut SmallRng,
+        distribution: &Distribution,
+        lengths: &Lengths,
+        count: usize,
+        unpredictable: bool,
+    ) -> Self {
+        if let Distribution::PerLength = distribution {
+            let selected = lengths.within(N);
+            let mut mixed = Vec::new();
+            if unpredictable && !selected.is_empty() {
+                let mixed_distr: Vec<UniformForLength<T>> = selected
+                    .iter()
+                    .map(|&len| uniform_distribution_for_length(len))
+                    .collect();
+                mixed.reserve_exact(count);
+                for i in 0..count {
+                    mixed.push(mixed_distr[i % selected.len()].sample(rng));
+                }
             }
-            mixed.shuffle(rng);
-            for (i, vec) in by_length.iter_mut().enumerate() {
-                let distr = uniform_distribution_for_length(i + 1);
-                vec.reserve_exact(count * 2);
-                vec.extend_from_slice(&mixed);
+            let mut by_length = [const { Vec::new() }; N];
+            for &len in &selected {
+                let vec = &mut by_length[len - 1];
+                let distr = uniform_distribution_for_length(len);
+                vec.reserve_exact(count);
                 for _ in 0..count {
                     vec.push(distr.sample(rng));
                 }
-                vec.shuffle(rng);
+            }
+            DataForType {
+                mixed,
+                by_length,
+                unpredictable,
+                sequences: Vec::new(),
             }
         } else {
-            for (i, vec) in by_length.iter_mut().enumerate() {
-                let distr = uniform_distribution_for_length(i + 1);
-                vec.reserve_exact(count);
-                for _ in 0..count {
-                    vec.push(distr.sample(rng));
+            // The distribution restricted to the selected lengths, sampled until
+            // it gives count values per selected length on average. Draws beyond
+            // the range of the type are left out like those of other lengths.
+            let selected = lengths.within(N);
+            let mut mixed = Vec::new();
+            if let Some(sampler) = distribution.sampler::<T>(&selected) {
+                let total = count * selected.len();
+                mixed.reserve_exact(total);
+                for _ in 0..total * ATTEMPTS {
+                    if mixed.len() == total {
+                        break;
+                    }
+                    if let Some(value) = sampler.sample(rng)
+                        && lengths.contains(value.length())
+                    {
+                        mixed.push(value);
+                    }
                 }
             }
+            DataForType::from_mixed(mixed, lengths, unpredictable)
+        }
+    }
+
+    fn from_values(values: &[Value], lengths: &Lengths, unpredictable: bool) -> Self {
+        let mixed = values
+            .iter()
+            .filter_map(|value| match *value {
+                Value::Unsigned(n) => T::try_from_u128(n),
+                Value::Signed(n) => T::try_from_i128(n),
+            })
+            .collect();
+        DataForType::from_mixed(mixed, lengths, unpredictable)
+    }
+
+    fn from_mixed(mut mixed: Vec<T>, lengths: &Lengths, unpredictable: bool) -> Self {
+        mixed.retain(|value| lengths.contains(value.length()));
+        let mut by_length = [const { Vec::new() }; N];
+        for &value in &mixed {
+            by_length[value.length() - 1].push(value);
         }
         DataForType {
-            count,
             mixed,
             by_length,
             unpredictable,
+            sequences: Vec::new(),
+        }
+    }
+
+    fn branch(rng: &mut SmallRng, models: &[Model], lengths: &Lengths, count: usize) -> Self {
+        let distr: [UniformForLength<T>; N] =
+            array::from_fn(|i| uniform_distribution_for_length(i + 1));
+        let selected = lengths.within(N);
+        let mut sequences = Vec::new();
+        for &model in models {
+            if selected.is_empty() {
+                break;
+            }
+            let lengths = model.lengths(rng, &selected, count);
+            sequences.push(Sequence {
+                label: model.label(),
+                entropy: model.entropy(&lengths, N),
+                values: lengths
+                    .iter()
+                    .map(|&len| distr[len - 1].sample(rng))
+                    .collect(),
+            });
+        }
+        DataForType {
+            mixed: Vec::new(),
+            by_length: [const { Vec::new() }; N],
+            unpredictable: false,
+            sequences,
+        }
+    }
+
+    // Every length group holds an increasing sequence that starts at a value of
+    // that length, possibly running into longer lengths.
+    fn monotonic(
+        rng: &mut SmallRng,
+        stride: u128,
+        jitter: u128,
+        lengths: &Lengths,
+        count: usize,
+    ) -> Self {
+        let mut by_length = [const { Vec::new() }; N];
+        for len in lengths.within(N) {
+            let vec = &mut by_length[len - 1];
+            let mut value = uniform_distribution_for_length::<T>(len).sample(rng);
+            vec.reserve_exact(count);
+            for _ in 0..count {
+                vec.push(value);
+                let step = stride.saturating_add(rng.random_range(0..=jitter));
+                match T::try_from_u128(step).and_then(|step| value.checked_add(step)) {
+                    Some(next) => value = next,
+                    None => break,
+                }
+            }
+        }
+        DataForType {
+            mixed: by_length.concat(),
+            by_length,
+            unpredictable: false,
+            sequences: Vec::new(),
+        }
+    }
+
+    // Every length group holds values within k of the threshold at which the
+    // length is reached: 10^(n-1) for n digits, and for signed types also
+    // -10^(n-2), where the minus sign makes n characters, half of them around
+    // each. About half of the values are one character shorter if k is small
+    // next to the threshold; a larger k also reaches shorter and longer lengths
+    // than those adjacent. Values are cut off at zero, which only the negative
+    // side includes, and at the type's minimum and maximum. No threshold leads
+    // to a length of 1, so that group is empty.
+    fn boundary(rng: &mut SmallRng, k: u128, lengths: &Lengths, count: usize) -> Self {
+        let k = T::try_from_u128(k).unwrap_or(T::MAX);
+        let mut by_length = [const { Vec::new() }; N];
+        for len in lengths.within(N) {
+            let vec = &mut by_length[len - 1];
+            let mut distrs = Vec::new();
+            if len >= 2
+                && let Some(power) = T::checked_pow10(len as u32 - 1)
+            {
+                let lo = if k < power { power - k } else { T::ZERO };
+                let hi = power.checked_add(k).unwrap_or(T::MAX);
+                distrs.push(Uniform::new_inclusive(lo, hi).unwrap());
+            }
+            if T::SIGNED
+                && len >= 2
+                && let Some(power) = T::checked_pow10(len as u32 - 2)
+            {
+                let threshold = power.wrapping_neg();
+                let lo = if k <= threshold - T::MIN {
+                    threshold - k
+                } else {
+                    T::MIN
+                };
+                let hi = threshold
+                    .checked_add(k)
+                    .filter(|&hi| hi < T::ZERO)
+                    .unwrap_or(T::ZERO);
+                distrs.push(Uniform::new_inclusive(lo, hi).unwrap());
+            }
+            if distrs.is_empty() {
+                continue;
+            }
+            vec.reserve_exact(count);
+            for i in 0..count {
+                vec.push(distrs[i % distrs.len()].sample(rng));
+            }
+        }
+        let mut mixed = by_length.concat();
+        mixed.shuffle(rng);
+        DataForType {
+            mixed,
+            by_length,
+            unpredictable: false,
+            sequences: Vec::new(),
         }
     }
 }
 
-fn uniform_distribution_for_length<T>(len: usize) -> Uniform<T>
+impl<T, const N: usize> DataForType<T, N>
 where
-    T: Unsigned,
+    T: Integer,
+{
+    pub fn is_empty(&self) -> bool {
+        self.by_length.iter().all(Vec::is_empty) && self.sequences.is_empty()
+    }
+
+    fn map<U>(self, f: fn(T) -> U) -> DataForType<U, N> {
+        let map = |vec: Vec<T>| vec.into_iter().map(f).collect();
+        DataForType {
+            mixed: map(self.mixed),
+            by_length: self.by_length.map(map),
+            unpredictable: self.unpredictable,
+            sequences: self
+                .sequences
+                .into_iter()
+                .map(|sequence| Sequence {
+                    label: sequence.label,
+                    entropy: sequence.entropy,
+                    values: map(sequence.values),
+                })
+                .collect(),
+        }
+    }
+
+    fn mix_into_lengths(&mut self, rng: &mut SmallRng, count: usize) {
+        self.mixed.truncate(count);
+        self.mixed.shuffle(rng);
+        for vec in &mut self.by_length {
+            if !vec.is_empty() {
+                vec.splice(0..0, self.mixed.iter().copied());
+                vec.shuffle(rng);
+            }
+        }
+    }
+
+    fn report(&self) {
+        let extra = if self.unpredictable {
+            self.mixed.len()
+        } else {
+            0
+        };
+        let lengths = self.by_length.iter().enumerate();
+        let nonempty = lengths.filter(|(_i, vec)| !vec.is_empty());
+        let total: usize = nonempty.clone().map(|(_i, vec)| vec.len() - extra).sum();
+        eprintln!("{}: {} values", any::type_name::<T>(), total);
+        for (i, vec) in nonempty {
+            eprintln!("  ({}, {})", i + 1, vec.len() - extra);
+        }
+    }
+}
+
+pub fn uniform_distribution_for_length<T>(len: usize) -> UniformForLength<T>
+where
+    T: Integer + SampleUniform,
 {
     assert!(len >= 1);
-    let lo = T::TEN.saturating_pow(len as u32 - 1) - T::from(len == 1);
-    assert!(lo != T::MAX);
-    let hi = T::TEN
-        .saturating_pow(len as u32)
-        .wrapping_add(T::ONE)
-        .saturating_sub(T::ONE)
-        .wrapping_sub(T::ONE);
-    Uniform::new_inclusive(lo, hi).unwrap()
+    let digits_range = |digits: usize| {
+        let lo = T::checked_pow10(digits as u32 - 1)?;
+        let hi = T::checked_pow10(digits as u32).map_or(T::MAX, |power| power - T::ONE);
+        Some((if digits == 1 { T::ZERO } else { lo }, hi))
+    };
+    let nonnegative = digits_range(len);
+    let negative = if T::SIGNED && len >= 2 {
+        // Negating the range of magnitudes, where the largest magnitude may
+        // exceed T::MAX and therefore has to be handled separately.
+        let lo = T::checked_pow10(len as u32 - 1)
+            .map_or(T::MIN, |power| (power - T::ONE).wrapping_neg());
+        let hi = T::checked_pow10(len as u32 - 2).map(T::wrapping_neg);
+        hi.map(|hi| (lo, hi))
+    } else {
+        None
+    };
+    let size = |(lo, hi): (T, T)| (hi - lo).as_f64() + 1.0;
+    let nonnegative_size = nonnegative.map_or(0.0, size);
+    let negative_size = negative.map_or(0.0, size);
+    let uniform = |(lo, hi)| Uniform::new_inclusive(lo, hi).unwrap();
+    let distr = UniformForLength {
+        nonnegative: nonnegative.map(uniform),
+        negative: negative.map(uniform),
+        negative_weight: negative_size / (nonnegative_size + negative_size),
+        size: nonnegative_size + negative_size,
+    };
+    assert!(distr.nonnegative.is_some() || distr.negative.is_some());
+    distr
+}
+
+impl<T> rand::distr::Distribution<T> for UniformForLength<T>
+where
+    T: Integer + SampleUniform,
+{
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        match (&self.nonnegative, &self.negative) {
+            (Some(nonnegative), Some(negative)) => {
+                if rng.random_bool(self.negative_weight) {
+                    negative.sample(rng)
+                } else {
+                    nonnegative.sample(rng)
+                }
+            }
+            (Some(distr), None) | (None, Some(distr)) => distr.sample(rng),
+            (None, None) => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataForType, Lengths};
+    use crate::Integer;
+    use crate::distribution::Distribution;
+    use rand::SeedableRng as _;
+    use rand::distr::uniform::SampleUniform;
+    use rand::rngs::SmallRng;
+
+    #[test]
+    fn test_lengths() {
+        let within = |arg, max_length| Lengths::parse(arg).unwrap().within(max_length);
+        assert_eq!(within("5", 10), [5]);
+        assert_eq!(within("1..=8", 10), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(within("3..5", 10), [3, 4]);
+        assert_eq!(within("..3", 10), [1, 2]);
+        assert_eq!(within("1,2,10..", 12), [1, 2, 10, 11, 12]);
+        assert_eq!(within("8..", 5), []);
+        assert_eq!(Lengths::all().within(3), [1, 2, 3]);
+        assert_eq!(Lengths::all().to_string(), "1..");
+        let lengths = Lengths::parse("3,1..=8,..3,10..").unwrap();
+        assert_eq!(lengths.to_string(), "3,1..=8,1..=2,10..");
+    }
+
+    #[test]
+    fn test_lengths_invalid() {
+        for arg in ["", "0", "0..3", "5..5", "5..=4", "x", "1,,2", "1..x"] {
+            assert!(Lengths::parse(arg).is_err(), "{arg}");
+        }
+    }
+
+    #[test]
+    fn test_by_length() {
+        fn check<T: Integer + SampleUniform, const N: usize>() -> DataForType<T, N> {
+            let mut rng = SmallRng::seed_from_u64(1);
+            let distribution = Distribution::PerLength;
+            let data =
+                DataForType::<T, N>::random(&mut rng, &distribution, &Lengths::all(), 1000, false);
+            for (i, vec) in data.by_length.iter().enumerate() {
+                assert_eq!(vec.len(), 1000);
+                assert!(vec.iter().all(|value| value.length() == i + 1));
+            }
+            data
+        }
+        let data = check::<i8, 4>();
+        assert!(data.by_length[3].contains(&i8::MIN));
+        assert!(data.by_length[1].iter().any(|&value| value < 0));
+        let data = check::<i32, 11>();
+        assert!(data.by_length[10].iter().all(|&value| value < -999_999_999));
+        assert!(data.by_length[1].iter().any(|&value| value < 0));
+        let data = check::<i128, 40>();
+        assert!(data.by_length[39].iter().all(|&value| value < 0));
+        assert!(data.by_length[38].iter().any(|&value| value < 0));
+    }
+
+    #[test]
+    fn test_boundary() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let data = DataForType::<i8, 4>::boundary(&mut rng, 2, &Lengths::all(), 100);
+        assert!(data.by_length[0].is_empty());
+        let within = |len: usize, ranges: &[(i8, i8)]| {
+            data.by_length[len - 1]
+                .iter()
+                .all(|value| ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(value)))
+        };
+        assert!(within(2, &[(8, 12), (-3, 0)]));
+        assert!(within(3, &[(98, 102), (-12, -8)]));
+        assert!(within(4, &[(-102, -98)]));
+        assert!(data.by_length[1].iter().any(|&value| value < 0));
+
+        let data = DataForType::<u8, 3>::boundary(&mut rng, 200, &Lengths::all(), 100);
+        assert!(data.by_length[1].iter().all(|&value| value <= 210));
+        assert!(data.by_length[2].iter().any(|&value| value < 100));
+    }
+
+    #[test]
+    fn test_distribution_within_lengths() {
+        let lengths = Lengths::parse("2..=3").unwrap();
+        for distribution in ["uniform", "log-uniform", "weights:1,1,1,1"] {
+            let distribution = Distribution::parse(distribution).unwrap();
+            let mut rng = SmallRng::seed_from_u64(1);
+            let data =
+                DataForType::<u32, 10>::random(&mut rng, &distribution, &lengths, 100, false);
+            assert_eq!(data.mixed.len(), 200);
+            assert!(
+                data.mixed
+                    .iter()
+                    .all(|value| lengths.contains(value.length()))
+            );
+            assert!(data.by_length[1].len() + data.by_length[2].len() == 200);
+        }
+    }
 }
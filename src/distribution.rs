@@ -0,0 +1,196 @@
+use crate::Integer;
+use crate::data::{UniformForLength, uniform_distribution_for_length};
+use anyhow::{Result, bail};
+use rand::RngExt as _;
+use rand::distr::Distribution as _;
+use rand::distr::uniform::SampleUniform;
+use rand::distr::weighted::WeightedIndex;
+use rand::rngs::SmallRng;
+use rand_distr::{Geometric, Zipf};
+
+pub enum Distribution {
+    PerLength,
+    Uniform,
+    LogUniform,
+    Zipf(f64),
+    Geometric(f64),
+    Weights(Vec<f64>),
+}
+
+pub enum Sampler<T>
+where
+    T: Integer + SampleUniform,
+{
+    LogUniform,
+    Zipf(Zipf<f64>),
+    Geometric(Geometric),
+    Weights(WeightedIndex<f64>, Vec<UniformForLength<T>>),
+}
+
+impl Distribution {
+    pub fn parse(arg: &str) -> Result<Self> {
+        let (name, param) = match arg.split_once(':') {
+            Some((name, param)) => (name, Some(param)),
+            None => (arg, None),
+        };
+        let distribution = match (name, param) {
+            ("per-length", None) => Distribution::PerLength,
+            ("uniform", None) => Distribution::Uniform,
+            ("log-uniform", None) => Distribution::LogUniform,
+            ("zipf", param) => {
+                let s = param.map_or(Ok(1.0), str::parse)?;
+                if Zipf::new(2.0, s).is_err() {
+                    bail!("invalid zipf exponent: {s}");
+                }
+                Distribution::Zipf(s)
+            }
+            ("geometric", param) => {
+                let p = param.map_or(Ok(0.01), str::parse)?;
+                if Geometric::new(p).is_err() {
+                    bail!("invalid geometric probability: {p}");
+                }
+                Distribution::Geometric(p)
+            }
+            ("weights", Some(param)) => {
+                let weights = param
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<Vec<f64>, _>>()?;
+                if WeightedIndex::new(&weights).is_err() {
+                    bail!("invalid length weights: {param}");
+                }
+                Distribution::Weights(weights)
+            }
+            _ => bail!("unsupported distribution: {arg}"),
+        };
+        Ok(distribution)
+    }
+
+    // None for PerLength, which is not a distribution over a single stream,
+    // and for weights that are all zero within the selected lengths of type T.
+    // Uniform and weighted distributions only produce values of the selected
+    // lengths; the others are sampled over the whole type and left to be
+    // filtered.
+    pub fn sampler<T>(&self, selected: &[usize]) -> Option<Sampler<T>>
+    where
+        T: Integer + SampleUniform,
+    {
+        match self {
+            Distribution::PerLength => None,
+            Distribution::Uniform => {
+                let distr: Vec<UniformForLength<T>> = selected
+                    .iter()
+                    .map(|&len| uniform_distribution_for_length(len))
+                    .collect();
+                let index = WeightedIndex::new(distr.iter().map(|distr| distr.size)).ok()?;
+                Some(Sampler::Weights(index, distr))
+            }
+            Distribution::LogUniform => Some(Sampler::LogUniform),
+            Distribution::Zipf(s) => {
+                let n = T::MAX.as_f64();
+                Some(Sampler::Zipf(Zipf::new(n, *s).unwrap()))
+            }
+            Distribution::Geometric(p) => Some(Sampler::Geometric(Geometric::new(*p).unwrap())),
+            Distribution::Weights(weights) => {
+                let weights = selected
+                    .iter()
+                    .map(|&len| weights.get(len - 1).copied().unwrap_or(0.0));
+                let index = WeightedIndex::new(weights).ok()?;
+                let distr = selected
+                    .iter()
+                    .map(|&len| uniform_distribution_for_length(len))
+                    .collect();
+                Some(Sampler::Weights(index, distr))
+            }
+        }
+    }
+}
+
+impl<T> Sampler<T>
+where
+    T: Integer + SampleUniform,
+{
+    // None for a draw beyond the range of T, which is left out rather than
+    // clamped to T::MAX.
+    pub fn sample(&self, rng: &mut SmallRng) -> Option<T> {
+        match self {
+            Sampler::LogUniform => {
+                let bits = rng.random_range(0..T::BITS - u32::from(T::SIGNED));
+                let mask = (1u128 << bits) - 1;
+                T::try_from_u128((1 << bits) | (rng.random::<u128>() & mask))
+            }
+            Sampler::Zipf(distr) => T::try_from_u128(distr.sample(rng) as u128),
+            Sampler::Geometric(distr) => T::try_from_u128(u128::from(distr.sample(rng))),
+            Sampler::Weights(index, distr) => Some(distr[index.sample(rng)].sample(rng)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Distribution;
+    use rand::SeedableRng as _;
+    use rand::rngs::SmallRng;
+
+    #[test]
+    fn test_parse() {
+        let parse = |arg| Distribution::parse(arg).unwrap();
+        assert!(matches!(parse("per-length"), Distribution::PerLength));
+        assert!(matches!(parse("uniform"), Distribution::Uniform));
+        assert!(matches!(parse("log-uniform"), Distribution::LogUniform));
+        assert!(matches!(parse("zipf"), Distribution::Zipf(1.0)));
+        assert!(matches!(parse("zipf:1.5"), Distribution::Zipf(1.5)));
+        assert!(matches!(parse("geometric"), Distribution::Geometric(0.01)));
+        assert!(matches!(
+            parse("geometric:0.5"),
+            Distribution::Geometric(0.5)
+        ));
+        let Distribution::Weights(weights) = parse("weights:1,0,2.5") else {
+            panic!();
+        };
+        assert_eq!(weights, [1.0, 0.0, 2.5]);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for arg in [
+            "",
+            "normal",
+            "uniform:1",
+            "zipf:x",
+            "zipf:-1",
+            "geometric:2",
+            "weights",
+            "weights:",
+            "weights:0,0",
+            "weights:1,-1",
+        ] {
+            assert!(Distribution::parse(arg).is_err(), "{arg}");
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let sampler = Distribution::Geometric(0.01).sampler::<u8>(&[1, 2, 3]);
+        let samples: Vec<Option<u8>> = (0..10000)
+            .map(|_| sampler.as_ref().unwrap().sample(&mut rng))
+            .collect();
+        assert!(samples.contains(&None));
+        let max = samples
+            .iter()
+            .filter(|&&value| value == Some(u8::MAX))
+            .count();
+        assert!(max < 100, "{max}");
+
+        let sampler = Distribution::Zipf(0.5).sampler::<i8>(&[1, 2, 3, 4]);
+        let samples: Vec<Option<i8>> = (0..10000)
+            .map(|_| sampler.as_ref().unwrap().sample(&mut rng))
+            .collect();
+        let max = samples
+            .iter()
+            .filter(|&&value| value == Some(i8::MAX))
+            .count();
+        assert!(max < 200, "{max}");
+    }
+}
@@ -0,0 +1,129 @@
+use crate::F;
+use crate::integer::Integer;
+use crate::timer;
+use std::hint;
+
+// Buckets per doubling of the logarithmic scale, each about 4% wide.
+const STEPS: f64 = 16.0;
+
+pub const PERCENTILES: [(&str, f64); 4] =
+    [("p50", 0.5), ("p90", 0.9), ("p99", 0.99), ("p99.9", 0.999)];
+
+// Times conversions in batches of the given size with the time stamp counter.
+// The rate and overhead of the counter are NaN and 0 until measured by
+// calibrate.
+#[derive(Copy, Clone)]
+pub struct Sampling {
+    pub batch: usize,
+    pub ticks_per_ns: f64,
+    // Ticks measured around an empty batch.
+    pub overhead: u64,
+}
+
+struct Histogram {
+    buckets: Vec<u64>,
+    total: u64,
+}
+
+impl Sampling {
+    pub fn calibrate(&mut self, ticks_per_ns: f64) {
+        self.ticks_per_ns = ticks_per_ns;
+        self.overhead = timer::tsc_overhead();
+    }
+
+    // Nanoseconds per value at each of the percentiles, less the given harness
+    // overhead in nanoseconds per value.
+    pub fn percentiles<T>(self, data: &[T], test: F<T>, overhead: f64) -> [f64; PERCENTILES.len()]
+    where
+        T: Integer,
+    {
+        let mut histogram = Histogram {
+            buckets: Vec::new(),
+            total: 0,
+        };
+        for batch in data.chunks(self.batch) {
+            let ticks = timer::tsc_ticks(|| {
+                for &value in batch {
+                    test(value, &|repr| {
+                        hint::black_box(repr);
+                    });
+                }
+            });
+            let ticks = ticks.saturating_sub(self.overhead) as f64 / batch.len() as f64;
+            histogram.record(f64::max(ticks - overhead * self.ticks_per_ns, 0.0));
+        }
+        PERCENTILES.map(|(_label, p)| histogram.percentile(p) / self.ticks_per_ns)
+    }
+}
+
+impl Histogram {
+    fn record(&mut self, value: f64) {
+        // Bucket 0 holds values below 1.
+        let bucket = if value < 1.0 {
+            0
+        } else {
+            1 + (value.log2() * STEPS) as usize
+        };
+        if bucket >= self.buckets.len() {
+            self.buckets.resize(bucket + 1, 0);
+        }
+        self.buckets[bucket] += 1;
+        self.total += 1;
+    }
+
+    // Geometric middle of the bucket holding the given fraction of values at or
+    // below it.
+    fn percentile(&self, p: f64) -> f64 {
+        let rank = (p * self.total as f64).ceil() as u64;
+        let mut seen = 0;
+        for (bucket, &count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= Ord::max(rank, 1) {
+                return if bucket == 0 {
+                    0.0
+                } else {
+                    2f64.powf((bucket as f64 - 0.5) / STEPS)
+                };
+            }
+        }
+        0.0
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::Histogram;
+
+    fn histogram(values: impl IntoIterator<Item = f64>) -> Histogram {
+        let mut histogram = Histogram {
+            buckets: Vec::new(),
+            total: 0,
+        };
+        for value in values {
+            histogram.record(value);
+        }
+        histogram
+    }
+
+    #[test]
+    fn test_percentile() {
+        let histogram = histogram((1..=1000).map(f64::from));
+        for (p, expected) in [(0.5, 500.0), (0.9, 900.0), (0.99, 990.0), (0.999, 999.0)] {
+            let actual = histogram.percentile(p);
+            assert!((actual / expected - 1.0).abs() < 0.03, "p{p}: {actual}");
+        }
+    }
+
+    #[test]
+    fn test_below_one() {
+        let histogram = histogram([0.0, 0.5, 0.5, 8.0]);
+        assert_eq!(histogram.percentile(0.5), 0.0);
+        assert!((histogram.percentile(0.9) / 8.0 - 1.0).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(histogram([]).percentile(0.5), 0.0);
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{Context as _, Result, bail};
+use std::fs;
+use std::path::Path;
+
+#[derive(Copy, Clone)]
+pub enum Value {
+    Unsigned(u128),
+    Signed(i128),
+}
+
+#[derive(Copy, Clone)]
+pub enum Format {
+    Text,
+    U32,
+    U64,
+    U128,
+}
+
+impl Format {
+    pub fn parse(arg: &str) -> Result<Self> {
+        Ok(match arg {
+            "text" => Format::Text,
+            "u32" => Format::U32,
+            "u64" => Format::U64,
+            "u128" => Format::U128,
+            _ => bail!("unsupported input format: {arg}"),
+        })
+    }
+}
+
+pub fn load(path: &Path, format: Format) -> Result<Vec<Value>> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let size = match format {
+        Format::Text => {
+            let text = str::from_utf8(&bytes)
+                .with_context(|| format!("{} is not valid UTF-8", path.display()))?;
+            let mut values = Vec::new();
+            for (i, line) in text.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let value = if line.starts_with('-') {
+                    line.parse().map(Value::Signed)
+                } else {
+                    line.parse().map(Value::Unsigned)
+                };
+                match value {
+                    Ok(value) => values.push(value),
+                    Err(_) => bail!("{}:{}: invalid value: {line}", path.display(), i + 1),
+                }
+            }
+            return Ok(values);
+        }
+        Format::U32 => 4,
+        Format::U64 => 8,
+        Format::U128 => 16,
+    };
+    if bytes.len() % size != 0 {
+        bail!(
+            "{} is not a whole number of {}-byte values",
+            path.display(),
+            size,
+        );
+    }
+    let mut le = [0u8; 16];
+    let values = bytes
+        .chunks_exact(size)
+        .map(|chunk| {
+            le[..size].copy_from_slice(chunk);
+            Value::Unsigned(u128::from_le_bytes(le))
+        })
+        .collect();
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, Value, load};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn write(name: &str, contents: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("itoa-benchmark-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn values(path: &Path, format: Format) -> Vec<(bool, i128)> {
+        let values = load(path, format).unwrap();
+        fs::remove_file(path).unwrap();
+        values
+            .into_iter()
+            .map(|value| match value {
+                Value::Unsigned(value) => (false, i128::try_from(value).unwrap()),
+                Value::Signed(value) => (true, value),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_text() {
+        let path = write("text", b"0\n  42 \n\n-7\n18446744073709551616\n");
+        let expected = [
+            (false, 0),
+            (false, 42),
+            (true, -7),
+            (false, 18_446_744_073_709_551_616),
+        ];
+        assert_eq!(values(&path, Format::Text), expected);
+    }
+
+    #[test]
+    fn test_binary() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        let path = write("u32", &bytes);
+        let expected = [(false, 1), (false, i128::from(u32::MAX))];
+        assert_eq!(values(&path, Format::U32), expected);
+
+        let path = write("u64", &u64::MAX.to_le_bytes());
+        assert_eq!(values(&path, Format::U64), [(false, i128::from(u64::MAX))]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let path = write("invalid", b"1\nx\n");
+        let error = load(&path, Format::Text).err().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(
+            error.to_string().ends_with(":2: invalid value: x"),
+            "{error}"
+        );
+
+        let path = write("partial", &[0; 6]);
+        assert!(load(&path, Format::U32).is_err());
+        fs::remove_file(&path).unwrap();
+
+        assert!(load(&path, Format::Text).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use std::ops::Sub;
+
+pub trait Integer: Copy + Send + Sync + 'static + Sub<Output = Self> + PartialOrd {
+    const ZERO: Self;
+    const ONE: Self;
+    const MIN: Self;
+    const MAX: Self;
+    const BITS: u32;
+    const SIGNED: bool;
+    fn checked_pow10(exp: u32) -> Option<Self>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn wrapping_neg(self) -> Self;
+    fn as_f64(self) -> f64;
+    fn try_from_u128(n: u128) -> Option<Self>;
+    fn try_from_i128(n: i128) -> Option<Self>;
+    // Number of characters in the decimal representation, including the minus
+    // sign of negative values.
+    fn length(self) -> usize;
+}
+
+macro_rules! impl_integer {
+    ($T:ty, $signed:literal, |$n:ident| $length:expr) => {
+        impl Integer for $T {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MIN: Self = Self::MIN;
+            const MAX: Self = Self::MAX;
+            const BITS: u32 = Self::BITS;
+            const SIGNED: bool = $signed;
+            fn checked_pow10(exp: u32) -> Option<Self> {
+                Self::checked_pow(10, exp)
+            }
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                self.checked_add(rhs)
+            }
+            fn wrapping_neg(self) -> Self {
+                self.wrapping_neg()
+            }
+            #[allow(clippy::cast_lossless)]
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
+            fn try_from_u128(n: u128) -> Option<Self> {
+                Self::try_from(n).ok()
+            }
+            fn try_from_i128(n: i128) -> Option<Self> {
+                Self::try_from(n).ok()
+            }
+            fn length(self) -> usize {
+                let $n = self;
+                $length
+            }
+        }
+    };
+}
+
+macro_rules! impl_unsigned {
+    ($($T:ty)*) => {
+        $(
+            impl_integer!($T, false, |n| n.checked_ilog10().map_or(1, |log| log as usize + 1));
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($T:ty)*) => {
+        $(
+            impl_integer!($T, true, |n| {
+                let digits = n.unsigned_abs().checked_ilog10().map_or(1, |log| log as usize + 1);
+                digits + usize::from(n < 0)
+            });
+        )*
+    };
+}
+
+impl_unsigned!(u8 u16 u32 u64 u128 usize);
+impl_signed!(i8 i16 i32 i64 i128 isize);
+
+#[cfg(test)]
+mod tests {
+    use super::Integer;
+
+    #[test]
+    fn test_length() {
+        assert_eq!(0u8.length(), 1);
+        assert_eq!(u8::MAX.length(), 3);
+        assert_eq!(u128::MAX.length(), 39);
+        assert_eq!((-5i8).length(), 2);
+        assert_eq!((-1i32).length(), 2);
+        assert_eq!((-9i32).length(), 2);
+        assert_eq!((-10i32).length(), 3);
+        assert_eq!(i8::MIN.length(), 4);
+        assert_eq!(i32::MIN.length(), 11);
+        assert_eq!(i128::MIN.length(), 40);
+        assert_eq!(i128::MAX.length(), 39);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::Trial;
+use rand::SeedableRng as _;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom as _;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+type TrialFn<'a> = Box<dyn Fn() -> Trial + 'a>;
+
+// Runs the trials of all measurements in randomized order, instead of one
+// measurement after another. The benchmark is walked twice: first to calibrate
+// every measurement and record a job for it without printing anything, then
+// after all jobs have run, to report their trials in the original order. The
+// harness overhead is measured as a job of its own next to each measurement it
+// is subtracted from, so no state outlives the two walks.
+pub struct Interleave<'a> {
+    state: RefCell<State<'a>>,
+}
+
+enum State<'a> {
+    Off,
+    Record(Vec<Job<'a>>),
+    Replay(VecDeque<Vec<Trial>>),
+}
+
+struct Job<'a> {
+    trial: TrialFn<'a>,
+    results: Vec<Trial>,
+}
+
+impl<'a> Interleave<'a> {
+    pub fn off() -> Self {
+        Interleave {
+            state: RefCell::new(State::Off),
+        }
+    }
+
+    pub fn record() -> Self {
+        Interleave {
+            state: RefCell::new(State::Record(Vec::new())),
+        }
+    }
+
+    pub fn recording(&self) -> bool {
+        matches!(*self.state.borrow(), State::Record(_))
+    }
+
+    pub fn push(&self, trial: TrialFn<'a>) {
+        if let State::Record(jobs) = &mut *self.state.borrow_mut() {
+            jobs.push(Job {
+                trial,
+                results: Vec::new(),
+            });
+        }
+    }
+
+    pub fn replay(&self) -> Option<Vec<Trial>> {
+        match &mut *self.state.borrow_mut() {
+            State::Replay(trials) => trials.pop_front(),
+            State::Off | State::Record(_) => None,
+        }
+    }
+
+    // Runs one trial of every recorded job per round, shuffling the jobs
+    // before each round.
+    pub fn run(&self, trials: usize, seed: u64) {
+        let mut state = self.state.borrow_mut();
+        let State::Record(jobs) = &mut *state else {
+            return;
+        };
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut order: Vec<usize> = (0..jobs.len()).collect();
+        for _round in 0..trials {
+            order.shuffle(&mut rng);
+            for &i in &order {
+                let result = (jobs[i].trial)();
+                jobs[i].results.push(result);
+            }
+        }
+        let results = jobs.drain(..).map(|job| job.results).collect();
+        *state = State::Replay(results);
+    }
+}
@@ -7,6 +7,7 @@
     clippy::many_single_char_names,
     clippy::nonminimal_bool,
     clippy::similar_names,
+    clippy::struct_excessive_bools,
     clippy::too_many_lines,
     clippy::unreadable_literal
 )]
@@ -15,13 +16,21 @@ mod args;
 mod bcd16;
 mod bcd4;
 mod bcd8;
+mod branch;
 mod branchlut;
 mod branchlut2;
+mod chart;
 mod count;
 mod countdecimaldigit;
+mod counters;
 mod countlut;
 mod data;
 mod digitslut;
+mod distribution;
+mod histogram;
+mod input;
+mod integer;
+mod interleave;
 mod itoa_jeaiii;
 mod itoa_ljust;
 mod lut;
@@ -29,98 +38,221 @@ mod mwilson;
 mod naive;
 #[cfg(int_format_into)]
 mod numbuffer;
+mod preset;
+mod report;
+mod stats;
+mod system;
 mod test_all;
+mod timer;
 mod tmueller;
 mod unnamed;
 mod unrolledlut;
-mod unsigned;
 mod yy;
 
-use crate::args::Type;
+use crate::args::{Args, Type};
+use crate::counters::Counts;
 use crate::data::{Data, DataForType};
-use crate::unsigned::Unsigned;
-use anyhow::Result;
+use crate::histogram::PERCENTILES;
+use crate::integer::Integer;
+use crate::interleave::Interleave;
+use crate::report::{Format, Value};
+use crate::stats::Summary;
+use crate::system::System;
+use anyhow::{Context as _, Result, bail};
 use arrayvec::ArrayString;
-use lexical_core::FormattedSize;
+use lexical_core::{FormattedSize, ToLexical};
 use std::any;
-use std::fmt::Write as _;
+use std::cell::Cell;
+use std::fmt::{Display, Write as _};
+use std::fs;
 use std::hint;
+use std::iter;
+use std::mem;
 use std::slice;
+use std::sync::Barrier;
+use std::thread;
 use std::time::{Duration, Instant};
 use to_arraystring::ToArrayString as _;
 
 const COUNT: usize = if cfg!(miri) { 20 } else { 100_000 };
 const TRIALS: usize = if cfg!(miri) { 1 } else { 8 };
 const PASSES: usize = if cfg!(miri) { 1 } else { 25 };
+const WARMUP: Duration = Duration::from_millis(10);
+const TRIAL_TIME: Duration = Duration::from_millis(10);
+const MAX_SPREAD: f64 = 0.05;
+const REMEASURE: usize = 2;
+
+// Sizes in bytes of the input array for --sweep, from within L1 to beyond the
+// last-level cache.
+const SWEEP: [usize; 9] = [
+    4 << 10,
+    16 << 10,
+    64 << 10,
+    256 << 10,
+    1 << 20,
+    4 << 20,
+    16 << 20,
+    64 << 20,
+    256 << 20,
+];
 
 type F<T> = fn(T, &dyn Fn(&str));
 
 #[derive(Copy, Clone)]
 struct Impl {
     name: &'static str,
+    // How the implementation is called, for the chart legend.
+    description: &'static str,
+    // Hex RGB of the implementation's lines in charts.
+    color: &'static str,
+    u8: Option<F<u8>>,
+    u16: Option<F<u16>>,
     u32: Option<F<u32>>,
     u64: Option<F<u64>>,
     u128: Option<F<u128>>,
+    usize: Option<F<usize>>,
+    i8: Option<F<i8>>,
+    i16: Option<F<i16>>,
+    i32: Option<F<i32>>,
+    i64: Option<F<i64>>,
+    i128: Option<F<i128>>,
+    isize: Option<F<isize>>,
+}
+
+impl Impl {
+    const NONE: Impl = Impl {
+        name: "",
+        description: "",
+        color: "",
+        u8: None,
+        u16: None,
+        u32: None,
+        u64: None,
+        u128: None,
+        usize: None,
+        i8: None,
+        i16: None,
+        i32: None,
+        i64: None,
+        i128: None,
+        isize: None,
+    };
+
+    fn types(&self) -> impl Iterator<Item = Type> {
+        [
+            self.u8.map(Type::U8),
+            self.u16.map(Type::U16),
+            self.u32.map(Type::U32),
+            self.u64.map(Type::U64),
+            self.u128.map(Type::U128),
+            self.usize.map(Type::Usize),
+            self.i8.map(Type::I8),
+            self.i16.map(Type::I16),
+            self.i32.map(Type::I32),
+            self.i64.map(Type::I64),
+            self.i128.map(Type::I128),
+            self.isize.map(Type::Isize),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+fn core_write<T: Display, const N: usize>(value: T, f: &dyn Fn(&str)) {
+    let mut buffer = ArrayString::<N>::new();
+    write!(buffer, "{value}").unwrap();
+    f(&buffer);
+}
+
+fn itoa_format<T: itoa::Integer>(value: T, f: &dyn Fn(&str)) {
+    f(itoa::Buffer::new().format(value));
+}
+
+fn lexical_write<T: ToLexical, const N: usize>(value: T, f: &dyn Fn(&str)) {
+    let mut buffer = [0u8; N];
+    let bytes = lexical_core::write(value, &mut buffer);
+    f(unsafe { str::from_utf8_unchecked(bytes) });
+}
+
+fn null<T>(_value: T, f: &dyn Fn(&str)) {
+    f("");
 }
 
 static IMPLS: &[Impl] = &[
     Impl {
         name: "core",
-        u32: Some(|value, f| {
-            let mut buffer = ArrayString::<10>::new();
-            write!(buffer, "{value}").unwrap();
-            f(&buffer);
-        }),
-        u64: Some(|value, f| {
-            let mut buffer = ArrayString::<20>::new();
-            write!(buffer, "{value}").unwrap();
-            f(&buffer);
-        }),
-        u128: Some(|value, f| {
-            let mut buffer = ArrayString::<39>::new();
-            write!(buffer, "{value}").unwrap();
-            f(&buffer);
-        }),
+        description: "std::write!(&mut buf, \"{}\", value)",
+        color: "1F77B4",
+        u8: Some(core_write::<u8, 3>),
+        u16: Some(core_write::<u16, 5>),
+        u32: Some(core_write::<u32, 10>),
+        u64: Some(core_write::<u64, 20>),
+        u128: Some(core_write::<u128, 39>),
+        usize: Some(core_write::<usize, 20>),
+        i8: Some(core_write::<i8, 4>),
+        i16: Some(core_write::<i16, 6>),
+        i32: Some(core_write::<i32, 11>),
+        i64: Some(core_write::<i64, 20>),
+        i128: Some(core_write::<i128, 40>),
+        isize: Some(core_write::<isize, 20>),
     },
     #[cfg(int_format_into)]
     Impl {
         name: "NumBuffer",
+        description: "value.format_into(&mut NumBuffer::new())",
+        color: "FF7F0E",
         u32: Some(numbuffer::u32toa_numbuffer),
         u64: Some(numbuffer::u64toa_numbuffer),
         u128: Some(numbuffer::u128toa_numbuffer),
+        ..Impl::NONE
     },
     Impl {
         name: "itoa",
-        u32: Some(|value, f| f(itoa::Buffer::new().format(value))),
-        u64: Some(|value, f| f(itoa::Buffer::new().format(value))),
-        u128: Some(|value, f| f(itoa::Buffer::new().format(value))),
+        description: "itoa::Buffer::new().format(value)",
+        color: "9467BD",
+        u8: Some(itoa_format),
+        u16: Some(itoa_format),
+        u32: Some(itoa_format),
+        u64: Some(itoa_format),
+        u128: Some(itoa_format),
+        usize: Some(itoa_format),
+        i8: Some(itoa_format),
+        i16: Some(itoa_format),
+        i32: Some(itoa_format),
+        i64: Some(itoa_format),
+        i128: Some(itoa_format),
+        isize: Some(itoa_format),
     },
     Impl {
         name: "lexical",
-        u32: Some(|value, f| {
-            let mut buffer = [0u8; u32::FORMATTED_SIZE_DECIMAL];
-            let bytes = lexical_core::write(value, &mut buffer);
-            f(unsafe { str::from_utf8_unchecked(bytes) });
-        }),
-        u64: Some(|value, f| {
-            let mut buffer = [0u8; u64::FORMATTED_SIZE_DECIMAL];
-            let bytes = lexical_core::write(value, &mut buffer);
-            f(unsafe { str::from_utf8_unchecked(bytes) });
-        }),
-        u128: Some(|value, f| {
-            let mut buffer = [0u8; u128::FORMATTED_SIZE_DECIMAL];
-            let bytes = lexical_core::write(value, &mut buffer);
-            f(unsafe { str::from_utf8_unchecked(bytes) });
-        }),
+        description: "lexical_core::write(value, &mut buf)",
+        color: "D62728",
+        u8: Some(lexical_write::<u8, { u8::FORMATTED_SIZE_DECIMAL }>),
+        u16: Some(lexical_write::<u16, { u16::FORMATTED_SIZE_DECIMAL }>),
+        u32: Some(lexical_write::<u32, { u32::FORMATTED_SIZE_DECIMAL }>),
+        u64: Some(lexical_write::<u64, { u64::FORMATTED_SIZE_DECIMAL }>),
+        u128: Some(lexical_write::<u128, { u128::FORMATTED_SIZE_DECIMAL }>),
+        usize: Some(lexical_write::<usize, { usize::FORMATTED_SIZE_DECIMAL }>),
+        i8: Some(lexical_write::<i8, { i8::FORMATTED_SIZE_DECIMAL }>),
+        i16: Some(lexical_write::<i16, { i16::FORMATTED_SIZE_DECIMAL }>),
+        i32: Some(lexical_write::<i32, { i32::FORMATTED_SIZE_DECIMAL }>),
+        i64: Some(lexical_write::<i64, { i64::FORMATTED_SIZE_DECIMAL }>),
+        i128: Some(lexical_write::<i128, { i128::FORMATTED_SIZE_DECIMAL }>),
+        isize: Some(lexical_write::<isize, { isize::FORMATTED_SIZE_DECIMAL }>),
     },
     Impl {
         name: "to-arraystring",
+        description: "value.to_arraystring()",
+        color: "8C564B",
         u32: Some(|value, f| f(&value.to_arraystring())),
         u64: Some(|value, f| f(&value.to_arraystring())),
         u128: Some(|value, f| f(&value.to_arraystring())),
+        ..Impl::NONE
     },
     Impl {
         name: "itoap",
+        description: "itoap::write_to_ptr(buf, value)",
+        color: "E377C2",
         u32: Some(|value, f| {
             let mut buffer = [0u8; <u32 as itoap::Integer>::MAX_LEN];
             let len = unsafe { itoap::write_to_ptr(buffer.as_mut_ptr(), value) };
@@ -136,9 +268,12 @@ static IMPLS: &[Impl] = &[
             let len = unsafe { itoap::write_to_ptr(buffer.as_mut_ptr(), value) };
             f(unsafe { str::from_utf8_unchecked(slice::from_raw_parts(buffer.as_ptr(), len)) });
         }),
+        ..Impl::NONE
     },
     Impl {
         name: "itoaaa",
+        description: "itoaaa::unchecked_write_to_slice(value, &mut buf)",
+        color: "17BECF",
         u32: Some(|value, f| {
             let mut buffer = [0u8; 10];
             let len = unsafe { itoaaa::unchecked_write_to_slice(value, &mut buffer) };
@@ -154,166 +289,1180 @@ static IMPLS: &[Impl] = &[
             let len = unsafe { itoaaa::unchecked_write_to_slice(value, &mut buffer) };
             f(unsafe { str::from_utf8_unchecked(slice::from_raw_parts(buffer.as_ptr(), len)) });
         }),
+        ..Impl::NONE
     },
     Impl {
         name: "null",
-        u32: Some(|_value, f| f("")),
-        u64: Some(|_value, f| f("")),
-        u128: Some(|_value, f| f("")),
+        description: "drop(value)",
+        color: "2CA02C",
+        u8: Some(null),
+        u16: Some(null),
+        u32: Some(null),
+        u64: Some(null),
+        u128: Some(null),
+        usize: Some(null),
+        i8: Some(null),
+        i16: Some(null),
+        i32: Some(null),
+        i64: Some(null),
+        i128: Some(null),
+        isize: Some(null),
     },
     Impl {
         name: "branchlut",
-        u32: None,
+        description: "branchlut::u64toa_branchlut(value, buf)",
+        color: "BCBD22",
         u64: Some(branchlut::u64toa_branchlut),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "branchlut2",
-        u32: None,
+        description: "branchlut2::u64toa_branchlut2(value, buf)",
+        color: "DBDB8D",
         u64: Some(branchlut2::u64toa_branchlut2),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "count",
-        u32: None,
+        description: "count::u64toa_count(value, buf)",
+        color: "7F7F7F",
         u64: Some(count::u64toa_count),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "countlut",
-        u32: None,
+        description: "countlut::u64toa_countlut(value, buf)",
+        color: "C7C7C7",
         u64: Some(countlut::u64toa_countlut),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "lut",
-        u32: None,
+        description: "lut::u64toa_lut(value, buf)",
+        color: "AEC7E8",
         u64: Some(lut::u64toa_lut),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "naive",
-        u32: None,
+        description: "naive::u64toa_naive(value, buf)",
+        color: "FFBB78",
         u64: Some(naive::u64toa_naive),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "amartin",
-        u32: None,
+        description: "itoa_ljust::u64toa_amartin(value, buf)",
+        color: "98DF8A",
         u64: Some(itoa_ljust::u64toa_amartin),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "bcd4",
+        description: "bcd4::u64toa_bcd4(value, buf)",
+        color: "FF9896",
         u32: Some(bcd4::u32toa_bcd4),
         u64: Some(bcd4::u64toa_bcd4),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "bcd8",
-        u32: None,
+        description: "bcd8::u64toa_bcd8(value, buf)",
+        color: "C5B0D5",
         u64: Some(bcd8::u64toa_bcd8),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "bcd16",
-        u32: None,
+        description: "bcd16::u64toa_bcd16(value, buf)",
+        color: "C49C94",
         u64: Some(bcd16::u64toa_bcd16),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "jeaiii",
-        u32: None,
+        description: "itoa_jeaiii::u64toa_jeaiii(value, buf)",
+        color: "F7B6D2",
         u64: Some(itoa_jeaiii::u64toa_jeaiii),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "mwilson",
-        u32: None,
+        description: "mwilson::u64toa_mwilson(value, buf)",
+        color: "9EDAE5",
         u64: Some(mwilson::u64toa_mwilson),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "tmueller",
+        description: "tmueller::u64toa_tmueller(value, buf)",
+        color: "393B79",
         u32: Some(tmueller::u32toa_tmueller),
         u64: Some(tmueller::u64toa_tmueller),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "unnamed",
-        u32: None,
+        description: "unnamed::u64toa_unnamed(value, buf)",
+        color: "637939",
         u64: Some(unnamed::u64toa_unnamed),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "unrolledlut",
-        u32: None,
+        description: "unrolledlut::u64toa_unrolledlut(value, buf)",
+        color: "8C6D31",
         u64: Some(unrolledlut::u64toa_unrolledlut),
-        u128: None,
+        ..Impl::NONE
     },
     Impl {
         name: "yy",
-        u32: None,
+        description: "yy::u64toa_yy(value, buf)",
+        color: "843C39",
         u64: Some(yy::u64toa_yy),
-        u128: None,
+        ..Impl::NONE
     },
 ];
 
-fn measure<T, const N: usize>(data: &DataForType<T, N>, test: F<T>)
+fn measure<'a, T, const N: usize>(
+    args: &'a Args,
+    interleave: &Interleave<'a>,
+    name: &'static str,
+    data: &'a DataForType<T, N>,
+    test: F<T>,
+    schedule: Schedule,
+) -> Vec<Record<'a>>
+where
+    T: Integer,
+{
+    let mut records = Vec::new();
+    if data.is_empty() {
+        return records;
+    }
+    let text = args.format == Format::Text;
+    if text && !interleave.recording() {
+        println!("  {}", any::type_name::<T>());
+    }
+    if args.sweep {
+        for (i, vec) in data.by_length.iter().enumerate() {
+            if !vec.is_empty() {
+                if text {
+                    println!("    length {}", i + 1);
+                }
+                sweep(args, name, schedule, i + 1, vec, test, &mut records);
+            }
+        }
+        return records;
+    }
+    if !args.threads.is_empty() {
+        for (i, vec) in data.by_length.iter().enumerate() {
+            if !vec.is_empty() {
+                if text {
+                    println!("    length {}", i + 1);
+                }
+                scale(args, name, schedule, Some(i + 1), vec, test, &mut records);
+            }
+        }
+        if !data.mixed.is_empty() {
+            if text {
+                println!("    mixed");
+            }
+            scale(args, name, schedule, None, &data.mixed, test, &mut records);
+        }
+        return records;
+    }
+    let baseline = data.unpredictable.then(|| {
+        let trials = measure_trials(args, interleave, schedule, args.passes, &data.mixed, test);
+        (Summary::new(trials.ns).get(args.estimator), trials.counts)
+    });
+    for (i, vec) in data.by_length.iter().enumerate() {
+        if vec.is_empty() {
+            continue;
+        }
+        // Subtracting the baseline removes the harness overhead too.
+        let measurement = if let Some((baseline, baseline_counts)) = &baseline {
+            let trials = measure_trials(args, interleave, schedule, args.passes, vec, test);
+            let n = (vec.len() - data.mixed.len()) as f64;
+            let samples = trials
+                .ns
+                .into_iter()
+                .map(|ns| f64::max(ns - baseline, 0.0) / n)
+                .collect();
+            let counts = trials.counts.map(|counts| match baseline_counts {
+                Some(baseline) => counts.minus(baseline).per(n),
+                None => counts.per(n),
+            });
+            Measurement {
+                raw: Summary::new(samples),
+                corrected: None,
+                counts,
+                interrupted: trials.interrupted,
+                discarded: trials.discarded,
+                unstable: trials.unstable,
+                percentiles: None,
+            }
+        } else {
+            let overhead = overhead(args, interleave, schedule, args.passes, vec);
+            summarize(
+                args,
+                interleave,
+                schedule,
+                args.passes,
+                vec,
+                test,
+                overhead.as_ref(),
+            )
+        };
+        print_point::<T>(
+            args,
+            interleave,
+            name,
+            Point::Length(i + 1),
+            measurement,
+            &mut records,
+        );
+    }
+    if !data.unpredictable && !data.mixed.is_empty() {
+        let overhead = overhead(args, interleave, schedule, args.passes, &data.mixed);
+        let measurement = summarize(
+            args,
+            interleave,
+            schedule,
+            args.passes,
+            &data.mixed,
+            test,
+            overhead.as_ref(),
+        );
+        print_point::<T>(
+            args,
+            interleave,
+            name,
+            Point::Mixed,
+            measurement,
+            &mut records,
+        );
+    }
+    for sequence in &data.sequences {
+        let overhead = overhead(args, interleave, schedule, args.passes, &sequence.values);
+        let measurement = summarize(
+            args,
+            interleave,
+            schedule,
+            args.passes,
+            &sequence.values,
+            test,
+            overhead.as_ref(),
+        );
+        let point = Point::Sequence {
+            label: &sequence.label,
+            entropy: sequence.entropy,
+        };
+        print_point::<T>(args, interleave, name, point, measurement, &mut records);
+    }
+    records
+}
+
+// Number of measurements taken of the data, including those of the harness
+// overhead and of the --unpredictable baseline.
+fn measurements<T, const N: usize>(args: &Args, data: &DataForType<T, N>) -> usize
 where
-    T: Unsigned,
+    T: Integer,
 {
-    println!("  {}", any::type_name::<T>());
-    let baseline = if data.unpredictable {
-        measure_once(&data.mixed, test)
+    if data.is_empty() {
+        return 0;
+    }
+    let lengths = data.by_length.iter().filter(|vec| !vec.is_empty()).count();
+    let mixed = usize::from(!data.mixed.is_empty());
+    // Every measurement corrected for the harness overhead is accompanied by one
+    // of the overhead.
+    let per_cell = if args.raw || data.unpredictable { 1 } else { 2 };
+    if args.sweep {
+        lengths * SWEEP.len() * per_cell
+    } else if !args.threads.is_empty() {
+        (lengths + mixed) * args.threads.len()
+    } else if data.unpredictable {
+        1 + lengths
     } else {
-        Duration::ZERO
+        (lengths + mixed + data.sequences.len()) * per_cell
+    }
+}
+
+// Repeats the values to fill each working-set size. A fixed number of passes
+// is scaled down so every size performs about the same number of conversions.
+fn sweep<T>(
+    args: &Args,
+    name: &'static str,
+    schedule: Schedule,
+    length: usize,
+    values: &[T],
+    test: F<T>,
+    records: &mut Vec<Record>,
+) where
+    T: Integer,
+{
+    for size in SWEEP {
+        let len = Ord::max(size / mem::size_of::<T>(), 1);
+        let data: Vec<T> = values.iter().copied().cycle().take(len).collect();
+        let passes = args
+            .passes
+            .map(|passes| (passes * values.len()).div_ceil(len));
+        let interleave = Interleave::off();
+        let overhead = overhead(args, &interleave, schedule, passes, &data);
+        let measurement = summarize(
+            args,
+            &interleave,
+            schedule,
+            passes,
+            &data,
+            test,
+            overhead.as_ref(),
+        );
+        let point = Point::Size {
+            length,
+            bytes: size,
+        };
+        print_point::<T>(args, &interleave, name, point, measurement, records);
+    }
+}
+
+// Runs the same number of passes over a copy of the data on every thread of
+// each placement at once, after each thread warms up on its own CPU. Prints the
+// nanoseconds per value taken by one thread, followed by the aggregate
+// nanoseconds per value across all threads and the figure of each thread. A
+// trial counts as interrupted if any thread was switched out during it.
+fn scale<'a, T>(
+    args: &'a Args,
+    name: &'static str,
+    schedule: Schedule,
+    length: Option<usize>,
+    data: &[T],
+    test: F<T>,
+    records: &mut Vec<Record<'a>>,
+) where
+    T: Integer,
+{
+    for placement in &args.threads {
+        let passes = warm_up(args, schedule, args.passes, data, test);
+        let barrier = Barrier::new(placement.cpus.len());
+        let per_thread: Vec<Vec<Trial>> = thread::scope(|scope| {
+            let threads: Vec<_> = placement
+                .cpus
+                .iter()
+                .map(|&cpu| {
+                    let barrier = &barrier;
+                    scope.spawn(move || {
+                        if let Some(cpu) = cpu
+                            && let Err(err) = system::pin(cpu)
+                        {
+                            eprintln!("warning: {err:#}");
+                        }
+                        let data = data.to_vec();
+                        warm_up(args, schedule, Some(passes), &data, test);
+                        let mut trials = Vec::with_capacity(args.trials);
+                        for _trial in 0..args.trials {
+                            barrier.wait();
+                            trials.push(measure_trial(args, passes, &data, test));
+                        }
+                        trials
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|thread| thread.join().unwrap())
+                .collect()
+        });
+        let n = per_thread.len() as f64;
+        let len = data.len() as f64;
+        let trials = |ns: &dyn Fn(usize) -> f64| {
+            let results = (0..args.trials)
+                .map(|trial| Trial {
+                    ns: ns(trial),
+                    counts: None,
+                    switches: per_thread.iter().map(|trials| trials[trial].switches).sum(),
+                })
+                .collect();
+            Trials::new(results, args.discard_interrupted)
+        };
+        let mean = trials(&|trial| {
+            per_thread
+                .iter()
+                .map(|trials| trials[trial].ns)
+                .sum::<f64>()
+                / n
+                / len
+        });
+        let aggregate = trials(&|trial| {
+            let slowest = per_thread
+                .iter()
+                .map(|trials| trials[trial].ns)
+                .fold(0.0, f64::max);
+            slowest / n / len
+        });
+        let point = Point::Threads {
+            length,
+            label: &placement.label,
+            aggregate: Summary::new(aggregate.ns).get(args.estimator),
+            per_thread: (0..per_thread.len())
+                .map(|thread| {
+                    let trials = trials(&|trial| per_thread[thread][trial].ns / len);
+                    Summary::new(trials.ns).get(args.estimator)
+                })
+                .collect(),
+        };
+        let measurement = Measurement {
+            raw: Summary::new(mean.ns),
+            corrected: None,
+            counts: None,
+            interrupted: mean.interrupted,
+            discarded: mean.discarded,
+            unstable: false,
+            percentiles: None,
+        };
+        print_point::<T>(args, &Interleave::off(), name, point, measurement, records);
+    }
+}
+
+struct Measurement {
+    raw: Summary,
+    // With the harness overhead subtracted.
+    corrected: Option<Summary>,
+    // Hardware or software events per value, with those of the harness
+    // overhead subtracted like the corrected summary.
+    counts: Option<Counts>,
+    // Number of kept trials that were interrupted by a context switch, and
+    // number of interrupted trials left out.
+    interrupted: usize,
+    discarded: usize,
+    // Trials remained noisy after measuring again.
+    unstable: bool,
+    // Nanoseconds per value of individually timed batches.
+    percentiles: Option<[f64; PERCENTILES.len()]>,
+}
+
+// The data that a measurement was taken over.
+enum Point<'a> {
+    Length(usize),
+    Mixed,
+    Sequence {
+        label: &'a str,
+        entropy: f64,
+    },
+    // Values of the given length repeated to fill a working set of the given
+    // size.
+    Size {
+        length: usize,
+        bytes: usize,
+    },
+    // Nanoseconds per value across all threads, and taken by each thread.
+    Threads {
+        length: Option<usize>,
+        label: &'a str,
+        aggregate: f64,
+        per_thread: Vec<f64>,
+    },
+}
+
+// Nanoseconds and events per pass of one trial, and the number of times the
+// thread was switched out during it.
+struct Trial {
+    ns: f64,
+    counts: Option<Counts>,
+    switches: u64,
+}
+
+// Nanoseconds per pass of each trial, and average events per pass.
+struct Trials {
+    ns: Vec<f64>,
+    counts: Option<Counts>,
+    interrupted: usize,
+    discarded: usize,
+    unstable: bool,
+}
+
+impl Trials {
+    // Leaves out trials that were interrupted by a context switch if asked to,
+    // unless all of them were.
+    fn new(results: Vec<Trial>, discard_interrupted: bool) -> Self {
+        let n = results.len() as f64;
+        let mut interrupted = results.iter().filter(|trial| trial.switches > 0).count();
+        let discard = discard_interrupted && interrupted < results.len();
+        let mut discarded = 0;
+        let mut ns = Vec::with_capacity(results.len());
+        let mut counts: Option<Counts> = None;
+        for trial in results {
+            if discard && trial.switches > 0 {
+                interrupted -= 1;
+                discarded += 1;
+            } else {
+                ns.push(trial.ns);
+            }
+            counts = match (counts, trial.counts) {
+                (Some(sum), Some(trial_counts)) => Some(sum.plus(&trial_counts)),
+                (sum, trial_counts) => sum.or(trial_counts),
+            };
+        }
+        let counts = counts.map(|counts| counts.per(n));
+        Trials {
+            ns,
+            counts,
+            interrupted,
+            discarded,
+            unstable: false,
+        }
+    }
+}
+
+// A result kept for --format json or csv and --chart, which are written once
+// all have been measured.
+struct Record<'a> {
+    name: &'static str,
+    ty: &'static str,
+    point: Point<'a>,
+    measurement: Measurement,
+}
+
+// Prints the result as text, or keeps it to be written in another format, and
+// for the chart.
+fn print_point<'a, T>(
+    args: &Args,
+    interleave: &Interleave,
+    name: &'static str,
+    point: Point<'a>,
+    measurement: Measurement,
+    records: &mut Vec<Record<'a>>,
+) {
+    if interleave.recording() {
+        return;
+    }
+    let ty = any::type_name::<T>();
+    if args.format == Format::Text {
+        print_text(args, &point, &measurement);
+    }
+    if args.format != Format::Text || args.chart.is_some() {
+        records.push(Record {
+            name,
+            ty,
+            point,
+            measurement,
+        });
+    }
+}
+
+fn print_text(args: &Args, point: &Point, measurement: &Measurement) {
+    let summary = measurement.corrected.as_ref().unwrap_or(&measurement.raw);
+    let estimate = summary.get(args.estimator);
+    let mut comments = Vec::new();
+    let (indent, x) = match point {
+        Point::Length(length) => ("    ", length.to_string()),
+        Point::Mixed => ("    ", "mixed".to_owned()),
+        Point::Sequence { label, entropy } => {
+            comments.push((*label).to_owned());
+            ("    ", format!("{entropy:.3}"))
+        }
+        Point::Size { length: _, bytes } => ("      ", (bytes >> 10).to_string()),
+        Point::Threads {
+            length: _,
+            label,
+            aggregate,
+            per_thread,
+        } => {
+            let mut threads = format!("aggregate {aggregate:.2}; threads");
+            for ns in per_thread {
+                write!(threads, " {ns:.2}").unwrap();
+            }
+            comments.push(threads);
+            ("      ", (*label).to_owned())
+        }
     };
-    for (i, vec) in data.by_length.iter().enumerate() {
-        let duration = measure_once(vec, test).saturating_sub(baseline);
-        println!(
-            "    ({}, {:.2})",
-            i + 1,
-            duration.as_secs_f64() * 1e9 / (PASSES * data.count) as f64,
+    if measurement.unstable {
+        comments.push("unstable".to_owned());
+    }
+    if measurement.interrupted > 0 {
+        comments.push(format!(
+            "interrupted {}/{}",
+            measurement.interrupted, args.trials,
+        ));
+    }
+    if measurement.discarded > 0 {
+        comments.push(format!(
+            "discarded {}/{}",
+            measurement.discarded, args.trials,
+        ));
+    }
+    if measurement.corrected.is_some() {
+        comments.push(format!("raw {:.2}", measurement.raw.get(args.estimator)));
+    }
+    if args.stats {
+        let mut stats = format!(
+            "min {:.2} median {:.2} mean {:.2} stddev {:.3} mad {:.3} ci {:.2}..{:.2} trials",
+            summary.min,
+            summary.median,
+            summary.mean,
+            summary.stddev,
+            summary.mad,
+            summary.ci.0,
+            summary.ci.1,
         );
+        for sample in &summary.samples {
+            write!(stats, " {sample:.2}").unwrap();
+        }
+        comments.push(stats);
+    }
+    if let Some(percentiles) = &measurement.percentiles {
+        let mut line = String::new();
+        for ((label, _p), ns) in PERCENTILES.iter().zip(percentiles) {
+            write!(
+                line,
+                "{}{label} {ns:.2}",
+                if line.is_empty() { "" } else { " " }
+            )
+            .unwrap();
+        }
+        comments.push(line);
+    }
+    if let Some(counts) = &measurement.counts {
+        comments.push(counts.to_string());
+    }
+    let mut line = format!("{indent}({x}, {estimate:.2})");
+    if !comments.is_empty() {
+        write!(line, " % {}", comments.join("; ")).unwrap();
+    }
+    println!("{line}");
+}
+
+fn summarize<'a, T>(
+    args: &'a Args,
+    interleave: &Interleave<'a>,
+    schedule: Schedule,
+    passes: Option<usize>,
+    data: &'a [T],
+    test: F<T>,
+    overhead: Option<&Overhead>,
+) -> Measurement
+where
+    T: Integer,
+{
+    let trials = measure_trials(args, interleave, schedule, passes, data, test);
+    let n = data.len() as f64;
+    let raw = Summary::new(trials.ns.into_iter().map(|ns| ns / n).collect());
+    let corrected = overhead.map(|overhead| {
+        Summary::new(
+            raw.samples
+                .iter()
+                .map(|ns| f64::max(ns - overhead.ns, 0.0))
+                .collect(),
+        )
+    });
+    let counts = trials.counts.map(|counts| {
+        let counts = counts.per(n);
+        match overhead.and_then(|overhead| overhead.counts.as_ref()) {
+            Some(overhead) => counts.minus(overhead),
+            None => counts,
+        }
+    });
+    Measurement {
+        raw,
+        corrected,
+        counts,
+        interrupted: trials.interrupted,
+        discarded: trials.discarded,
+        unstable: trials.unstable,
+        percentiles: args
+            .sampling
+            .filter(|_| !interleave.recording())
+            .map(|sampling| {
+                warm_up(args, schedule, Some(1), data, test);
+                let overhead = overhead.map_or(0.0, |overhead| overhead.ns);
+                sampling.percentiles(data, test, overhead)
+            }),
     }
 }
 
-fn measure_once<T>(data: &[T], test: F<T>) -> Duration
+// Nanoseconds and events per value spent in the benchmark loop and callback.
+struct Overhead {
+    ns: f64,
+    counts: Option<Counts>,
+}
+
+// Measures the harness overhead by running the null implementation over the
+// same data, unless --raw.
+fn overhead<'a, T>(
+    args: &'a Args,
+    interleave: &Interleave<'a>,
+    schedule: Schedule,
+    passes: Option<usize>,
+    data: &'a [T],
+) -> Option<Overhead>
 where
-    T: Unsigned,
+    T: Integer,
 {
-    let mut duration = Duration::MAX;
-    for _trial in 0..TRIALS {
+    if args.raw {
+        return None;
+    }
+    let trials = measure_trials(args, interleave, schedule, passes, data, null::<T>);
+    let n = data.len() as f64;
+    Some(Overhead {
+        ns: Summary::new(trials.ns).get(args.estimator) / n,
+        counts: trials.counts.map(|counts| counts.per(n)),
+    })
+}
+
+fn measure_trials<'a, T>(
+    args: &'a Args,
+    interleave: &Interleave<'a>,
+    schedule: Schedule,
+    passes: Option<usize>,
+    data: &'a [T],
+    test: F<T>,
+) -> Trials
+where
+    T: Integer,
+{
+    let attempt = || {
+        let passes = warm_up(args, schedule, passes, data, test);
+        let results = (0..args.trials)
+            .map(|_trial| measure_trial(args, passes, data, test))
+            .collect();
+        Trials::new(results, args.discard_interrupted)
+    };
+    let (trials, remeasure) = match interleave.replay() {
+        // Measuring again would fall outside of the interleaved order.
+        Some(results) => (Trials::new(results, args.discard_interrupted), 0),
+        None if interleave.recording() => {
+            let passes = warm_up(args, schedule, passes, data, test);
+            // Interleaved with other measurements, so each trial starts with a
+            // pass to bring the data and branch history back in.
+            interleave.push(Box::new(move || {
+                run(args, 1, data, test);
+                measure_trial(args, passes, data, test)
+            }));
+            return Trials {
+                ns: vec![0.0; args.trials],
+                counts: None,
+                interrupted: 0,
+                discarded: 0,
+                unstable: false,
+            };
+        }
+        None => (attempt(), args.remeasure),
+    };
+    // Measures again while the trials are too noisy, keeping the least noisy
+    // attempt.
+    let mut best = (Summary::new(trials.ns.clone()).spread(), trials);
+    for _attempt in 0..remeasure {
+        if best.0 <= args.max_spread {
+            break;
+        }
+        let trials = attempt();
+        let spread = Summary::new(trials.ns.clone()).spread();
+        if spread < best.0 {
+            best = (spread, trials);
+        }
+    }
+    let (spread, mut trials) = best;
+    trials.unstable = spread > args.max_spread;
+    trials
+}
+
+fn measure_trial<T>(args: &Args, passes: usize, data: &[T], test: F<T>) -> Trial
+where
+    T: Integer,
+{
+    if let Some(counters) = &args.counters {
+        counters.start();
+    }
+    let switches = system::context_switches();
+    let ns = args.timer.time(|| run(args, passes, data, test));
+    let switches = system::context_switches().saturating_sub(switches);
+    let counts = args
+        .counters
+        .as_ref()
+        .map(|counters| counters.stop().per(passes as f64));
+    Trial {
+        ns: ns / passes as f64,
+        counts,
+        switches,
+    }
+}
+
+// Runs the conversions for the warmup time, then unless the number of passes is
+// fixed, calibrates it so that one trial takes about the target time.
+fn warm_up<T>(
+    args: &Args,
+    schedule: Schedule,
+    passes: Option<usize>,
+    data: &[T],
+    test: F<T>,
+) -> usize
+where
+    T: Integer,
+{
+    let begin = Instant::now();
+    while begin.elapsed() < schedule.warmup {
+        run(args, 1, data, test);
+    }
+    if let Some(passes) = passes {
+        return passes;
+    }
+    let mut passes = 1;
+    loop {
         let begin = Instant::now();
-        for _pass in 0..PASSES {
-            for &value in data {
-                test(value, &|repr| {
-                    hint::black_box(repr);
-                });
+        run(args, passes, data, test);
+        let elapsed = begin.elapsed();
+        if elapsed >= schedule.trial / 4 {
+            let scale = schedule.trial.as_secs_f64() / elapsed.as_secs_f64();
+            return Ord::max((passes as f64 * scale) as usize, 1);
+        }
+        passes *= 2;
+    }
+}
+
+fn run<T>(args: &Args, passes: usize, data: &[T], test: F<T>)
+where
+    T: Integer,
+{
+    if args.latency {
+        return run_dependent(passes, data, test);
+    }
+    for _pass in 0..passes {
+        for &value in data {
+            test(value, &|repr| {
+                hint::black_box(repr);
+            });
+        }
+    }
+}
+
+// Picks each value by an index that depends on the previous output's length,
+// so a conversion cannot start before the previous one has produced its length.
+// Only the length is on this chain: writing the digits, which most
+// implementations do after computing the length, can still overlap with the
+// next conversion. No output is longer than 40 bytes, so the index is never
+// actually moved, and moving it backward would stay in bounds anyway.
+fn run_dependent<T>(passes: usize, data: &[T], test: F<T>)
+where
+    T: Integer,
+{
+    let len = Cell::new(0);
+    for _pass in 0..passes {
+        for i in 0..data.len() {
+            let dependency = usize::from(len.get() > 64);
+            test(data[i.saturating_sub(dependency)], &|repr| {
+                len.set(hint::black_box(repr).len());
+            });
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Schedule {
+    warmup: Duration,
+    trial: Duration,
+}
+
+impl Schedule {
+    // Shrinks the warmup and trial time if needed so that every measurement of
+    // the benchmark entry fits in its share of the time budget, and fails if
+    // even the shortest measurements would not.
+    fn new<T, const N: usize>(
+        args: &Args,
+        name: &str,
+        data: &DataForType<T, N>,
+        test: F<T>,
+    ) -> Result<Self>
+    where
+        T: Integer,
+    {
+        let mut schedule = Schedule {
+            warmup: args.warmup,
+            trial: args.trial_time,
+        };
+        let measurements = measurements(args, data);
+        let Some(budget) = args.time_budget else {
+            return Ok(schedule);
+        };
+        if measurements == 0 {
+            return Ok(schedule);
+        }
+        let share = budget.as_secs_f64() / (args.benchmark.len() * measurements) as f64;
+
+        // Every trial runs at least one pass over the data, so time one over
+        // the largest data of the entry after an untimed one, or for --sweep,
+        // estimate one over the average working set.
+        let largest = iter::once(&data.mixed)
+            .chain(&data.by_length)
+            .chain(data.sequences.iter().map(|sequence| &sequence.values))
+            .max_by_key(|vec| vec.len())
+            .unwrap();
+        run(args, 1, largest, test);
+        let begin = Instant::now();
+        run(args, 1, largest, test);
+        let mut pass = begin.elapsed().as_secs_f64();
+        if args.sweep {
+            let len = SWEEP.map(|size| Ord::max(size / mem::size_of::<T>(), 1));
+            let mean = len.iter().sum::<usize>() as f64 / SWEEP.len() as f64;
+            pass *= mean / largest.len() as f64;
+        }
+
+        // Seconds per measurement with the warmup and trial time scaled by the
+        // given factor. Each attempt warms up, calibrates the passes over about
+        // one trial, and runs every trial for at least one pass. Noisy
+        // measurements are attempted again unless interleaved, whose trials
+        // instead each start with one more pass. Percentiles take another
+        // warmup and pass.
+        let trials = args.trials as f64;
+        let (attempts, extra) = if args.order_seed.is_some() {
+            (1.0, trials)
+        } else {
+            ((args.remeasure + 1) as f64, 0.0)
+        };
+        let warmup = args.warmup.as_secs_f64();
+        let trial = args.trial_time.as_secs_f64();
+        let cost = |scale: f64| {
+            let attempt =
+                scale * warmup + (trials + 1.0) * f64::max(scale * trial, pass) + extra * pass;
+            let percentiles = if args.sampling.is_some() {
+                scale * warmup + pass
+            } else {
+                0.0
+            };
+            attempts * attempt + percentiles
+        };
+        if cost(0.0) > share {
+            bail!(
+                "--time-budget is too short for {name}:{}: each of its {measurements} measurements takes at least {:.1} ms, but its share is {:.1} ms; lower --count, --trials or --remeasure, or raise the budget",
+                any::type_name::<T>(),
+                cost(0.0) * 1e3,
+                share * 1e3,
+            );
+        }
+        if cost(1.0) > share {
+            let (mut lo, mut hi) = (0.0, 1.0);
+            for _step in 0..64 {
+                let mid = f64::midpoint(lo, hi);
+                if cost(mid) <= share {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
             }
+            schedule.warmup = args.warmup.mul_f64(lo);
+            schedule.trial = args.trial_time.mul_f64(lo);
         }
-        duration = Ord::min(duration, begin.elapsed());
+        Ok(schedule)
     }
-    duration
 }
 
 fn main() -> Result<()> {
-    let args = args::parse()?;
-    let data = Data::random(COUNT, args.unpredictable);
-    let mut prev_name = None;
+    let mut args = args::parse()?;
+    if let Some(path) = &args.save_manifest {
+        fs::write(path, &args.manifest)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    if let Some(cpu) = args.cpu {
+        system::pin(cpu)?;
+    }
+    if let Some(nice) = args.nice {
+        system::set_nice(nice)?;
+    }
+    args.calibrate()?;
+    let system = System::detect(args.cpu);
+    system.warn();
+    if args.format == Format::Text {
+        println!("% {system}");
+        if let Some(seed) = args.order_seed {
+            println!("% order seed {seed}");
+        }
+    }
+
+    let types: Vec<&str> = args.benchmark.iter().map(|(_name, f)| f.name()).collect();
+    let data = Data::new(
+        &args.source,
+        &args.lengths,
+        args.count,
+        args.seed,
+        args.unpredictable,
+        &types,
+    )?;
+    let schedules = args
+        .benchmark
+        .iter()
+        .map(|&(name, f)| match f {
+            Type::U8(f) => Schedule::new(&args, name, data.u8.as_ref().unwrap(), f),
+            Type::U16(f) => Schedule::new(&args, name, data.u16.as_ref().unwrap(), f),
+            Type::U32(f) => Schedule::new(&args, name, data.u32.as_ref().unwrap(), f),
+            Type::U64(f) => Schedule::new(&args, name, data.u64.as_ref().unwrap(), f),
+            Type::U128(f) => Schedule::new(&args, name, data.u128.as_ref().unwrap(), f),
+            Type::Usize(f) => Schedule::new(&args, name, data.usize.as_ref().unwrap(), f),
+            Type::I8(f) => Schedule::new(&args, name, data.i8.as_ref().unwrap(), f),
+            Type::I16(f) => Schedule::new(&args, name, data.i16.as_ref().unwrap(), f),
+            Type::I32(f) => Schedule::new(&args, name, data.i32.as_ref().unwrap(), f),
+            Type::I64(f) => Schedule::new(&args, name, data.i64.as_ref().unwrap(), f),
+            Type::I128(f) => Schedule::new(&args, name, data.i128.as_ref().unwrap(), f),
+            Type::Isize(f) => Schedule::new(&args, name, data.isize.as_ref().unwrap(), f),
+        })
+        .collect::<Result<Vec<Schedule>>>()?;
+    let interleave = match args.order_seed {
+        Some(seed) => {
+            let interleave = Interleave::record();
+            measure_all(&args, &interleave, &data, &schedules);
+            interleave.run(args.trials, seed);
+            interleave
+        }
+        None => Interleave::off(),
+    };
+    let records = measure_all(&args, &interleave, &data, &schedules);
 
-    for (name, f) in args.benchmark {
+    let values = || -> Vec<Value> {
+        records
+            .iter()
+            .map(|record| report::record(&args, record))
+            .collect()
+    };
+    match args.format {
+        Format::Text => {}
+        Format::Json => print!("{}", report::json(&report::run(&args, &system), &values())),
+        Format::Csv => print!("{}", report::csv(&report::run(&args, &system), &values())),
+    }
+    if let Some(path) = &args.chart {
+        let entries: Vec<chart::Entry> = records
+            .iter()
+            .filter_map(|record| match record.point {
+                // Raw figures, like the published chart, on which the null
+                // implementation shows the harness overhead.
+                Point::Length(length) if chart::TYPES.contains(&record.ty) => Some(chart::Entry {
+                    name: record.name,
+                    ty: record.ty,
+                    length,
+                    ns: record.measurement.raw.get(args.estimator),
+                }),
+                _ => None,
+            })
+            .collect();
+        let chart = if path.extension().is_some_and(|extension| extension == "svg") {
+            chart::svg(&entries, args.highlight)
+        } else {
+            chart::tex(&entries, args.highlight)
+        };
+        fs::write(path, chart).with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn measure_all<'a>(
+    args: &'a Args,
+    interleave: &Interleave<'a>,
+    data: &'a Data,
+    schedules: &[Schedule],
+) -> Vec<Record<'a>> {
+    let mut records = Vec::new();
+    let mut prev_name = None;
+    for (&(name, f), &schedule) in args.benchmark.iter().zip(schedules) {
         if prev_name != Some(name) {
-            println!("{newline}{name}", newline = prev_name.map_or("", |_| "\n"));
+            if args.format == Format::Text && !interleave.recording() {
+                println!("{newline}{name}", newline = prev_name.map_or("", |_| "\n"));
+            }
             prev_name = Some(name);
         }
-        match f {
-            Type::U32(f) => measure(&data.u32, f),
-            Type::U64(f) => measure(&data.u64, f),
-            Type::U128(f) => measure(&data.u128, f),
-        }
+        records.extend(match f {
+            Type::U8(f) => measure(
+                args,
+                interleave,
+                name,
+                data.u8.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::U16(f) => measure(
+                args,
+                interleave,
+                name,
+                data.u16.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::U32(f) => measure(
+                args,
+                interleave,
+                name,
+                data.u32.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::U64(f) => measure(
+                args,
+                interleave,
+                name,
+                data.u64.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::U128(f) => measure(
+                args,
+                interleave,
+                name,
+                data.u128.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::Usize(f) => measure(
+                args,
+                interleave,
+                name,
+                data.usize.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::I8(f) => measure(
+                args,
+                interleave,
+                name,
+                data.i8.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::I16(f) => measure(
+                args,
+                interleave,
+                name,
+                data.i16.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::I32(f) => measure(
+                args,
+                interleave,
+                name,
+                data.i32.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::I64(f) => measure(
+                args,
+                interleave,
+                name,
+                data.i64.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::I128(f) => measure(
+                args,
+                interleave,
+                name,
+                data.i128.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+            Type::Isize(f) => measure(
+                args,
+                interleave,
+                name,
+                data.isize.as_ref().unwrap(),
+                f,
+                schedule,
+            ),
+        });
     }
-
-    Ok(())
+    records
 }
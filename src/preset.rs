@@ -0,0 +1,87 @@
+use anyhow::{Result, bail};
+use rand::RngExt as _;
+use rand::distr::Distribution as _;
+use rand::rngs::SmallRng;
+use rand::seq::IndexedRandom as _;
+use rand_distr::LogNormal;
+
+#[derive(Copy, Clone)]
+pub enum Preset {
+    Timestamps,
+    RowIds,
+    ContentLength,
+    Ports,
+    ByteCounters,
+    Uuids,
+}
+
+impl Preset {
+    pub fn parse(arg: &str) -> Result<Self> {
+        Ok(match arg {
+            "timestamps" => Preset::Timestamps,
+            "row-ids" => Preset::RowIds,
+            "content-length" => Preset::ContentLength,
+            "ports" => Preset::Ports,
+            "byte-counters" => Preset::ByteCounters,
+            "uuids" => Preset::Uuids,
+            _ => bail!("unsupported preset: {arg}"),
+        })
+    }
+
+    pub fn generate(self, rng: &mut SmallRng, count: usize) -> Vec<u128> {
+        let mut values = Vec::with_capacity(count);
+        match self {
+            // Milliseconds since the Unix epoch, between 2020 and 2027.
+            Preset::Timestamps => {
+                for _ in 0..count {
+                    values.push(rng.random_range(1_577_836_800_000..1_798_761_600_000));
+                }
+            }
+            // Primary keys of tables whose sizes range from a thousand to a
+            // billion rows, each row equally likely to be accessed.
+            Preset::RowIds => {
+                for _ in 0..count {
+                    let rows = 10f64.powf(rng.random_range(3.0..9.0)) as u128;
+                    values.push(rng.random_range(1..=rows));
+                }
+            }
+            // Response sizes with a median around 5 KB and a long tail.
+            Preset::ContentLength => {
+                let distr = LogNormal::new(5000f64.ln(), 2.0).unwrap();
+                for _ in 0..count {
+                    values.push(distr.sample(rng) as u128);
+                }
+            }
+            // Mostly ephemeral client ports, some well-known server ports, and
+            // the registered range in between.
+            Preset::Ports => {
+                const WELL_KNOWN: [u128; 8] = [22, 53, 80, 443, 3306, 5432, 6379, 8080];
+                for _ in 0..count {
+                    values.push(match rng.random_range(0..10) {
+                        0..5 => rng.random_range(49152..=65535),
+                        5..8 => *WELL_KNOWN.choose(rng).unwrap(),
+                        _ => rng.random_range(1024..49152),
+                    });
+                }
+            }
+            // Cumulative byte counts spread evenly across magnitudes up to
+            // 256 TiB.
+            Preset::ByteCounters => {
+                for _ in 0..count {
+                    let bits = rng.random_range(0..48);
+                    let mask = (1u128 << bits) - 1;
+                    values.push((1 << bits) | (rng.random::<u128>() & mask));
+                }
+            }
+            // Version 4 UUIDs interpreted as 128-bit integers.
+            Preset::Uuids => {
+                for _ in 0..count {
+                    let random = rng.random::<u128>();
+                    let version = (random & !(0xF << 76)) | (0x4 << 76);
+                    values.push((version & !(0x3 << 62)) | (0x2 << 62));
+                }
+            }
+        }
+        values
+    }
+}
@@ -0,0 +1,424 @@
+use crate::args::Args;
+use crate::histogram::PERCENTILES;
+use crate::system::System;
+use crate::timer::Timer;
+use crate::{Point, Record};
+use anyhow::{Result, bail};
+use std::fmt::Write as _;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn parse(arg: &str) -> Result<Self> {
+        Ok(match arg {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "csv" => Format::Csv,
+            _ => bail!("unsupported output format: {arg}"),
+        })
+    }
+}
+
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(u64),
+    Float(f64),
+    Str(String),
+    List(Vec<f64>),
+    Object(Vec<(&'static str, Value)>),
+}
+
+impl<T> From<Option<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Value::Int(value as u64)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+// Options and system settings that every record was measured under.
+pub fn run(args: &Args, system: &System) -> Value {
+    Value::Object(vec![
+        ("version", env!("CARGO_PKG_VERSION").into()),
+        (
+            "source",
+            if args.source_options.is_empty() {
+                "--distribution per-length".to_owned()
+            } else {
+                args.source_options.join(" ")
+            }
+            .into(),
+        ),
+        ("lengths", args.lengths.to_string().into()),
+        ("unpredictable", args.unpredictable.into()),
+        ("seed", args.seed.into()),
+        ("order_seed", args.order_seed.into()),
+        ("count", args.count.into()),
+        ("trials", args.trials.into()),
+        ("passes", args.passes.into()),
+        ("warmup_ms", (args.warmup.as_secs_f64() * 1e3).into()),
+        (
+            "trial_time_ms",
+            (args.trial_time.as_secs_f64() * 1e3).into(),
+        ),
+        ("timer", args.timer.name().into()),
+        (
+            "tsc_ghz",
+            match args.timer {
+                Timer::Tsc(ticks_per_ns) => Some(ticks_per_ns),
+                Timer::Instant | Timer::ThreadCpu => None,
+            }
+            .into(),
+        ),
+        ("discard_interrupted", args.discard_interrupted.into()),
+        ("max_spread_percent", (args.max_spread * 100.0).into()),
+        ("remeasure", args.remeasure.into()),
+        ("estimator", args.estimator.name().into()),
+        ("latency", args.latency.into()),
+        ("raw", args.raw.into()),
+        ("cpu", system.cpu.into()),
+        ("pinned", system.pinned.into()),
+        ("governor", system.governor.clone().into()),
+        ("turbo", system.turbo.into()),
+        ("smt", system.smt.into()),
+        ("siblings", system.siblings.clone().into()),
+    ])
+}
+
+pub fn record(args: &Args, record: &Record) -> Value {
+    let Record {
+        name,
+        ty,
+        point,
+        measurement,
+    } = record;
+    let summary = measurement.corrected.as_ref().unwrap_or(&measurement.raw);
+    let estimate = summary.get(args.estimator);
+    let (mut length, mut sequence, mut entropy, mut size, mut threads) =
+        (None, None, None, None, None);
+    let (mut aggregate, mut per_thread) = (None, None);
+    match point {
+        Point::Length(n) => length = Some(*n),
+        Point::Mixed => {}
+        Point::Sequence {
+            label,
+            entropy: bits,
+        } => {
+            sequence = Some(*label);
+            entropy = Some(*bits);
+        }
+        Point::Size { length: n, bytes } => {
+            length = Some(*n);
+            size = Some(*bytes);
+        }
+        Point::Threads {
+            length: n,
+            label,
+            aggregate: ns,
+            per_thread: list,
+        } => {
+            length = *n;
+            threads = Some(*label);
+            aggregate = Some(*ns);
+            per_thread = Some(list.clone());
+        }
+    }
+    let mixed = length.is_none() && sequence.is_none();
+    let mut fields = vec![
+        ("impl", (*name).into()),
+        ("type", (*ty).into()),
+        ("length", length.into()),
+        ("mixed", mixed.into()),
+        ("sequence", sequence.into()),
+        ("entropy", entropy.into()),
+        ("size", size.into()),
+        ("threads", threads.into()),
+        ("estimator", args.estimator.name().into()),
+        ("ns", estimate.into()),
+        ("raw_ns", measurement.raw.get(args.estimator).into()),
+        ("min", summary.min.into()),
+        ("median", summary.median.into()),
+        ("mean", summary.mean.into()),
+        ("stddev", summary.stddev.into()),
+        ("mad", summary.mad.into()),
+        ("ci_low", summary.ci.0.into()),
+        ("ci_high", summary.ci.1.into()),
+        ("unstable", measurement.unstable.into()),
+        ("interrupted", measurement.interrupted.into()),
+        ("discarded", measurement.discarded.into()),
+        ("aggregate_ns", aggregate.into()),
+        ("thread_ns", per_thread.map_or(Value::Null, Value::List)),
+    ];
+    if let Some(percentiles) = &measurement.percentiles {
+        let percentiles = PERCENTILES
+            .iter()
+            .zip(percentiles)
+            .map(|(&(label, _p), &ns)| (label, ns.into()))
+            .collect();
+        fields.push(("percentiles", Value::Object(percentiles)));
+    }
+    if let Some(counts) = &measurement.counts {
+        let counts = counts
+            .values()
+            .iter()
+            .map(|&(name, value)| (name, value.into()))
+            .collect();
+        fields.push(("counters", Value::Object(counts)));
+    }
+    fields.push(("samples", Value::List(summary.samples.clone())));
+    fields.push(("raw_samples", Value::List(measurement.raw.samples.clone())));
+    Value::Object(fields)
+}
+
+// One object with the run metadata and an array of the records.
+pub fn json(run: &Value, records: &[Value]) -> String {
+    let mut out = String::from("{\n  \"run\": ");
+    write_json(&mut out, run);
+    out.push_str(",\n  \"results\": [");
+    for (i, record) in records.iter().enumerate() {
+        out.push_str(if i == 0 { "\n    " } else { ",\n    " });
+        write_json(&mut out, record);
+    }
+    out.push_str(if records.is_empty() {
+        "]\n}\n"
+    } else {
+        "\n  ]\n}\n"
+    });
+    out
+}
+
+fn write_json(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(value) => write!(out, "{value}").unwrap(),
+        Value::Int(value) => write!(out, "{value}").unwrap(),
+        Value::Float(value) => write_float(out, *value),
+        Value::Str(value) => {
+            out.push('"');
+            for ch in value.chars() {
+                match ch {
+                    '"' => out.push_str("\\\""),
+                    '\\' => out.push_str("\\\\"),
+                    '\n' => out.push_str("\\n"),
+                    ch if ch < ' ' => write!(out, "\\u{:04x}", u32::from(ch)).unwrap(),
+                    ch => out.push(ch),
+                }
+            }
+            out.push('"');
+        }
+        Value::List(values) => {
+            out.push('[');
+            for (i, &value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_float(out, value);
+            }
+            out.push(']');
+        }
+        Value::Object(fields) => {
+            out.push('{');
+            for (i, (key, value)) in fields.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write!(out, "\"{key}\": ").unwrap();
+                write_json(out, value);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_float(out: &mut String, value: f64) {
+    if value.is_finite() {
+        write!(out, "{value}").unwrap();
+    } else {
+        out.push_str("null");
+    }
+}
+
+// One row per record, preceded by the run metadata in comment lines. Nested
+// objects become one column per field, named `parent.field`, lists of samples
+// are separated by spaces, and missing or non-finite values are left empty.
+pub fn csv(run: &Value, records: &[Value]) -> String {
+    let mut out = String::new();
+    let mut metadata = Vec::new();
+    flatten(&mut metadata, "", run);
+    for (key, value) in metadata {
+        let line = format!("# {key}: {value}");
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+    let rows: Vec<Vec<(String, String)>> = records
+        .iter()
+        .map(|record| {
+            let mut row = Vec::new();
+            flatten(&mut row, "", record);
+            row
+        })
+        .collect();
+    let mut columns: Vec<&str> = Vec::new();
+    for row in &rows {
+        for (column, _cell) in row {
+            if !columns.contains(&column.as_str()) {
+                columns.push(column);
+            }
+        }
+    }
+    write_row(&mut out, columns.iter().copied());
+    for row in &rows {
+        write_row(
+            &mut out,
+            columns.iter().map(|&column| {
+                row.iter()
+                    .find(|(name, _cell)| name == column)
+                    .map_or("", |(_name, cell)| cell.as_str())
+            }),
+        );
+    }
+    out
+}
+
+fn flatten(row: &mut Vec<(String, String)>, prefix: &str, value: &Value) {
+    let Value::Object(fields) = value else {
+        return;
+    };
+    for (key, value) in fields {
+        let column = format!("{prefix}{key}");
+        let cell = match value {
+            Value::Null => String::new(),
+            Value::Bool(value) => value.to_string(),
+            Value::Int(value) => value.to_string(),
+            Value::Float(value) => csv_float(*value),
+            Value::Str(value) => value.clone(),
+            Value::List(values) => {
+                let values: Vec<String> = values.iter().copied().map(csv_float).collect();
+                values.join(" ")
+            }
+            Value::Object(_) => {
+                flatten(row, &format!("{column}."), value);
+                continue;
+            }
+        };
+        row.push((column, cell));
+    }
+}
+
+fn csv_float(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::new()
+    }
+}
+
+fn write_row<'a>(out: &mut String, cells: impl Iterator<Item = &'a str>) {
+    for (i, cell) in cells.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if cell.contains([',', '"', '\n']) {
+            write!(out, "\"{}\"", cell.replace('"', "\"\"")).unwrap();
+        } else {
+            out.push_str(cell);
+        }
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Value, csv, json};
+
+    #[test]
+    fn test_json() {
+        let run = Value::Object(vec![("name", "a\"b\\c\nd\u{1}".into())]);
+        let record = Value::Object(vec![
+            ("ns", f64::NAN.into()),
+            ("min", 1.5.into()),
+            ("samples", Value::List(vec![1.0, f64::INFINITY])),
+            ("length", None::<usize>.into()),
+        ]);
+        let expected = concat!(
+            "{\n",
+            "  \"run\": {\"name\": \"a\\\"b\\\\c\\nd\\u0001\"},\n",
+            "  \"results\": [\n",
+            "    {\"ns\": null, \"min\": 1.5, \"samples\": [1, null], \"length\": null}\n",
+            "  ]\n",
+            "}\n",
+        );
+        assert_eq!(json(&run, &[record]), expected);
+        assert!(json(&run, &[]).ends_with(",\n  \"results\": []\n}\n"));
+    }
+
+    #[test]
+    fn test_csv() {
+        let run = Value::Object(vec![
+            ("version", "1.0".into()),
+            ("cpu", None::<usize>.into()),
+        ]);
+        let records = [
+            Value::Object(vec![
+                ("impl", "a,b".into()),
+                ("ns", f64::NAN.into()),
+                ("samples", Value::List(vec![1.0, f64::NAN, 2.5])),
+            ]),
+            Value::Object(vec![
+                ("impl", "say \"hi\"\n".into()),
+                ("ns", 3.0.into()),
+                ("counters", Value::Object(vec![("cycles", 4.0.into())])),
+            ]),
+        ];
+        let expected = concat!(
+            "# version: 1.0\n",
+            "# cpu:\n",
+            "impl,ns,samples,counters.cycles\n",
+            "\"a,b\",,1  2.5,\n",
+            "\"say \"\"hi\"\"\n\",3,,4\n",
+        );
+        assert_eq!(csv(&run, &records), expected);
+    }
+}
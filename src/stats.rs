@@ -0,0 +1,162 @@
+use anyhow::{Result, bail};
+use rand::RngExt as _;
+use rand::SeedableRng as _;
+use rand::rngs::SmallRng;
+
+const BOOTSTRAP: usize = 1000;
+
+#[derive(Copy, Clone)]
+pub enum Estimator {
+    Min,
+    Median,
+    Mean,
+}
+
+impl Estimator {
+    pub fn parse(arg: &str) -> Result<Self> {
+        Ok(match arg {
+            "min" => Estimator::Min,
+            "median" => Estimator::Median,
+            "mean" => Estimator::Mean,
+            _ => bail!("unsupported estimator: {arg}"),
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Estimator::Min => "min",
+            Estimator::Median => "median",
+            Estimator::Mean => "mean",
+        }
+    }
+}
+
+// Nanoseconds per value of each trial, and estimators over them.
+pub struct Summary {
+    pub samples: Vec<f64>,
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    // Median absolute deviation from the median, unscaled.
+    pub mad: f64,
+    // 95% bootstrap confidence interval of the median.
+    pub ci: (f64, f64),
+}
+
+impl Summary {
+    pub fn new(samples: Vec<f64>) -> Self {
+        let mut sorted = samples.clone();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len();
+        let min = sorted[0];
+        let median = median(&sorted);
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let stddev = if n > 1 {
+            let variance = sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+            variance.sqrt()
+        } else {
+            0.0
+        };
+        let mut deviations: Vec<f64> = sorted.iter().map(|x| (x - median).abs()).collect();
+        deviations.sort_by(f64::total_cmp);
+        let mad = self::median(&deviations);
+
+        // Fixed seed so that the same samples always give the same interval.
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut medians = Vec::with_capacity(BOOTSTRAP);
+        let mut resample = vec![0.0; n];
+        for _ in 0..BOOTSTRAP {
+            for x in &mut resample {
+                *x = sorted[rng.random_range(0..n)];
+            }
+            resample.sort_by(f64::total_cmp);
+            medians.push(self::median(&resample));
+        }
+        medians.sort_by(f64::total_cmp);
+        let ci = (
+            medians[BOOTSTRAP * 25 / 1000],
+            medians[BOOTSTRAP * 975 / 1000 - 1],
+        );
+
+        Summary {
+            samples,
+            min,
+            median,
+            mean,
+            stddev,
+            mad,
+            ci,
+        }
+    }
+
+    // Median absolute deviation relative to the median.
+    pub fn spread(&self) -> f64 {
+        if self.median == 0.0 {
+            0.0
+        } else {
+            self.mad / self.median
+        }
+    }
+
+    pub fn get(&self, estimator: Estimator) -> f64 {
+        match estimator {
+            Estimator::Min => self.min,
+            Estimator::Median => self.median,
+            Estimator::Mean => self.mean,
+        }
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let n = sorted.len();
+    if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        f64::midpoint(sorted[n / 2 - 1], sorted[n / 2])
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::Summary;
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::new(vec![5.0, 1.0, 3.0, 2.0, 4.0]);
+        assert_eq!(summary.samples, [5.0, 1.0, 3.0, 2.0, 4.0]);
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.median, 3.0);
+        assert_eq!(summary.mean, 3.0);
+        assert_eq!(summary.stddev, 2.5f64.sqrt());
+        assert_eq!(summary.mad, 1.0);
+        assert_eq!(summary.spread(), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_even_median() {
+        let summary = Summary::new(vec![4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(summary.median, 2.5);
+        assert_eq!(summary.mad, 1.0);
+    }
+
+    #[test]
+    fn test_single_sample() {
+        let summary = Summary::new(vec![7.0]);
+        assert_eq!(summary.stddev, 0.0);
+        assert_eq!(summary.mad, 0.0);
+        assert_eq!(summary.ci, (7.0, 7.0));
+    }
+
+    #[test]
+    fn test_bootstrap_ci() {
+        let samples: Vec<f64> = (1..=9).map(f64::from).collect();
+        let summary = Summary::new(samples.clone());
+        let (low, high) = summary.ci;
+        assert!(1.0 <= low && low <= summary.median);
+        assert!(summary.median <= high && high <= 9.0);
+        assert!(low < high);
+        assert_eq!(Summary::new(samples).ci, summary.ci);
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::{Context as _, Result, bail};
+use std::fmt::{self, Display};
+use std::fs;
+
+pub use self::sys::{context_switches, pin, set_nice};
+
+// Settings of the CPU that the benchmark runs on, as far as sysfs exposes them.
+pub struct System {
+    pub cpu: Option<usize>,
+    pub pinned: bool,
+    pub governor: Option<String>,
+    pub turbo: Option<bool>,
+    pub smt: Option<bool>,
+    pub siblings: Option<String>,
+}
+
+impl System {
+    pub fn detect(pinned: Option<usize>) -> Self {
+        let cpu = pinned.or_else(sys::current_cpu);
+        let governor = cpu.and_then(|cpu| read(&format!("cpu{cpu}/cpufreq/scaling_governor")));
+        let turbo = match read("intel_pstate/no_turbo") {
+            Some(no_turbo) => Some(no_turbo == "0"),
+            None => read("cpufreq/boost").map(|boost| boost == "1"),
+        };
+        let smt = read("smt/active").map(|active| active == "1");
+        let siblings = cpu.and_then(|cpu| read(&format!("cpu{cpu}/topology/thread_siblings_list")));
+        System {
+            cpu,
+            pinned: pinned.is_some(),
+            governor,
+            turbo,
+            smt,
+            siblings,
+        }
+    }
+
+    pub fn warn(&self) {
+        if !self.pinned {
+            eprintln!("warning: measuring thread is not pinned to a CPU; use --cpu");
+        }
+        if let Some(governor) = &self.governor
+            && governor != "performance"
+        {
+            eprintln!("warning: cpufreq governor is {governor}, not performance");
+        }
+        if self.turbo == Some(true) {
+            eprintln!("warning: turbo boost is enabled");
+        }
+        if self.smt == Some(true) {
+            match &self.siblings {
+                Some(siblings) if siblings.contains([',', '-']) => {
+                    eprintln!("warning: SMT is active; CPU shares a core with {siblings}");
+                }
+                _ => eprintln!("warning: SMT is active"),
+            }
+        }
+    }
+}
+
+impl Display for System {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fn or_unknown(value: Option<impl Display>) -> String {
+            value.map_or_else(|| "unknown".to_owned(), |value| value.to_string())
+        }
+        let on_off = |value: Option<bool>| value.map(|on| if on { "on" } else { "off" });
+        write!(
+            formatter,
+            "cpu {}{}; governor {}; turbo {}; smt {}; siblings {}",
+            or_unknown(self.cpu),
+            if self.pinned { " pinned" } else { "" },
+            or_unknown(self.governor.as_ref()),
+            or_unknown(on_off(self.turbo)),
+            or_unknown(on_off(self.smt)),
+            or_unknown(self.siblings.as_ref()),
+        )
+    }
+}
+
+// CPUs that threads of one measurement are pinned to, or None for each thread
+// if the topology is unknown.
+pub struct Placement {
+    pub label: String,
+    pub cpus: Vec<Option<usize>>,
+}
+
+// Parses a comma-separated list of thread counts, each placed one thread per
+// physical core, and `smt` for one thread on each hardware thread of a core.
+pub fn placements(spec: &str, first: Option<usize>) -> Result<Vec<Placement>> {
+    let cores = cores(first);
+    let mut placements = Vec::new();
+    for item in spec.split(',') {
+        if item == "smt" {
+            let Some(core) = cores.first().filter(|core| core.len() > 1) else {
+                bail!("--threads smt needs a CPU with SMT siblings");
+            };
+            placements.push(Placement {
+                label: item.to_owned(),
+                cpus: core.iter().copied().map(Some).collect(),
+            });
+            continue;
+        }
+        let n: usize = item
+            .parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .with_context(|| format!("invalid number of threads: {item}"))?;
+        let cpus = if cores.is_empty() {
+            vec![None; n]
+        } else if n <= cores.len() {
+            cores[..n].iter().map(|core| Some(core[0])).collect()
+        } else {
+            bail!(
+                "cannot run {n} threads on separate cores; {} cores are online",
+                cores.len()
+            );
+        };
+        placements.push(Placement {
+            label: item.to_owned(),
+            cpus,
+        });
+    }
+    Ok(placements)
+}
+
+// Online CPUs grouped by physical core, starting with the given CPU.
+fn cores(first: Option<usize>) -> Vec<Vec<usize>> {
+    let Some(online) = read("online") else {
+        return Vec::new();
+    };
+    let mut cores: Vec<Vec<usize>> = Vec::new();
+    for cpu in parse_list(&online) {
+        if cores.iter().any(|core| core.contains(&cpu)) {
+            continue;
+        }
+        let siblings = read(&format!("cpu{cpu}/topology/thread_siblings_list"));
+        cores.push(siblings.map_or_else(|| vec![cpu], |siblings| parse_list(&siblings)));
+    }
+    if let Some(first) = first
+        && let Some(i) = cores.iter().position(|core| core.contains(&first))
+    {
+        let mut core = cores.remove(i);
+        core.retain(|&cpu| cpu != first);
+        core.insert(0, first);
+        cores.insert(0, core);
+    }
+    cores
+}
+
+// Parses the kernel's CPU list format, like `0-3,8`.
+fn parse_list(list: &str) -> Vec<usize> {
+    let mut cpus = Vec::new();
+    for range in list.split(',') {
+        let (start, end) = range.split_once('-').unwrap_or((range, range));
+        if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse()) {
+            cpus.extend(start..=end);
+        }
+    }
+    cpus
+}
+
+fn read(path: &str) -> Option<String> {
+    fs::read_to_string(format!("/sys/devices/system/cpu/{path}"))
+        .ok()
+        .map(|content| content.trim().to_owned())
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use anyhow::{Context as _, Result, bail};
+    use std::io;
+    use std::mem;
+
+    pub fn pin(cpu: usize) -> Result<()> {
+        if cpu >= libc::CPU_SETSIZE as usize {
+            bail!("invalid cpu: {cpu}");
+        }
+        let result = unsafe {
+            let mut set: libc::cpu_set_t = mem::zeroed();
+            libc::CPU_SET(cpu, &mut set);
+            libc::sched_setaffinity(0, mem::size_of::<libc::cpu_set_t>(), &raw const set)
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| format!("failed to pin to cpu {cpu}"));
+        }
+        Ok(())
+    }
+
+    // Applies to the calling thread only, which on Linux has its own nice
+    // value.
+    pub fn set_nice(nice: i32) -> Result<()> {
+        let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) };
+        if result != 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| format!("failed to set nice value {nice}"));
+        }
+        Ok(())
+    }
+
+    pub fn current_cpu() -> Option<usize> {
+        usize::try_from(unsafe { libc::sched_getcpu() }).ok()
+    }
+
+    // Voluntary and involuntary context switches of the calling thread.
+    pub fn context_switches() -> u64 {
+        let mut usage: libc::rusage = unsafe { mem::zeroed() };
+        if unsafe { libc::getrusage(libc::RUSAGE_THREAD, &raw mut usage) } != 0 {
+            return 0;
+        }
+        (usage.ru_nvcsw + usage.ru_nivcsw) as u64
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use anyhow::{Result, bail};
+
+    pub fn pin(_cpu: usize) -> Result<()> {
+        bail!("--cpu is only supported on Linux");
+    }
+
+    pub fn set_nice(_nice: i32) -> Result<()> {
+        bail!("--nice is only supported on Linux");
+    }
+
+    pub fn current_cpu() -> Option<usize> {
+        None
+    }
+
+    pub fn context_switches() -> u64 {
+        0
+    }
+}
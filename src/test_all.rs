@@ -1,13 +1,15 @@
 #![cfg(test)]
 
-use crate::{Data, DataForType, F, Impl, Unsigned};
+use crate::data::{Lengths, Source, TYPES};
+use crate::distribution::Distribution;
+use crate::{Data, DataForType, F, Impl, Integer};
 use std::sync::atomic::{AtomicBool, Ordering};
 
 const COUNT: usize = if cfg!(miri) { 10 } else { 1000 };
 
 fn verify<T, const N: usize>(core: &Impl, data: &DataForType<T, N>, get: fn(&Impl) -> Option<F<T>>)
 where
-    T: Unsigned,
+    T: Integer,
 {
     let core = get(core).unwrap();
     for vec in &data.by_length {
@@ -42,8 +44,18 @@ fn test_all() {
     }
 
     let core = core.unwrap();
-    let data = Data::random(COUNT, false);
-    verify(core, &data.u32, |imp| imp.u32);
-    verify(core, &data.u64, |imp| imp.u64);
-    verify(core, &data.u128, |imp| imp.u128);
+    let source = Source::Random(Distribution::PerLength);
+    let data = Data::new(&source, &Lengths::all(), COUNT, 1, false, &TYPES).unwrap();
+    verify(core, data.u8.as_ref().unwrap(), |imp| imp.u8);
+    verify(core, data.u16.as_ref().unwrap(), |imp| imp.u16);
+    verify(core, data.u32.as_ref().unwrap(), |imp| imp.u32);
+    verify(core, data.u64.as_ref().unwrap(), |imp| imp.u64);
+    verify(core, data.u128.as_ref().unwrap(), |imp| imp.u128);
+    verify(core, data.usize.as_ref().unwrap(), |imp| imp.usize);
+    verify(core, data.i8.as_ref().unwrap(), |imp| imp.i8);
+    verify(core, data.i16.as_ref().unwrap(), |imp| imp.i16);
+    verify(core, data.i32.as_ref().unwrap(), |imp| imp.i32);
+    verify(core, data.i64.as_ref().unwrap(), |imp| imp.i64);
+    verify(core, data.i128.as_ref().unwrap(), |imp| imp.i128);
+    verify(core, data.isize.as_ref().unwrap(), |imp| imp.isize);
 }
@@ -0,0 +1,152 @@
+use anyhow::{Result, bail};
+use std::time::{Duration, Instant};
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::{__cpuid, __rdtscp, _mm_lfence, _rdtsc};
+
+const CALIBRATION: Duration = Duration::from_millis(100);
+
+#[derive(Copy, Clone)]
+pub enum Timer {
+    Instant,
+    // CPU time consumed by the calling thread, which excludes time while it is
+    // preempted.
+    ThreadCpu,
+    // Time stamp counter, with the number of ticks per nanosecond, which is NaN
+    // until measured by calibrate.
+    Tsc(f64),
+}
+
+impl Timer {
+    pub fn parse(arg: &str) -> Result<Self> {
+        Ok(match arg {
+            "instant" => Timer::Instant,
+            "thread-cpu" if cfg!(target_os = "linux") => Timer::ThreadCpu,
+            "thread-cpu" => bail!("the thread-cpu timer is only supported on Linux"),
+            "tsc" if cfg!(target_arch = "x86_64") => Timer::Tsc(f64::NAN),
+            "tsc" => bail!("the tsc timer is only supported on x86_64"),
+            _ => bail!("unsupported timer: {arg}"),
+        })
+    }
+
+    pub fn calibrate(self) -> Result<Self> {
+        Ok(match self {
+            Timer::Instant | Timer::ThreadCpu => self,
+            Timer::Tsc(_) => Timer::Tsc(calibrate_tsc()?),
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Timer::Instant => "instant",
+            Timer::ThreadCpu => "thread-cpu",
+            Timer::Tsc(_) => "tsc",
+        }
+    }
+
+    // Nanoseconds taken by the given function.
+    pub fn time(self, f: impl FnOnce()) -> f64 {
+        match self {
+            Timer::Instant => {
+                let begin = Instant::now();
+                f();
+                begin.elapsed().as_secs_f64() * 1e9
+            }
+            Timer::ThreadCpu => {
+                let begin = thread_cpu_time();
+                f();
+                thread_cpu_time().saturating_sub(begin).as_secs_f64() * 1e9
+            }
+            Timer::Tsc(ticks_per_ns) => tsc_ticks(f) as f64 / ticks_per_ns,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn thread_cpu_time() -> Duration {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &raw mut time) };
+    Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn thread_cpu_time() -> Duration {
+    unreachable!()
+}
+
+// Time stamp counter ticks taken by the given function.
+pub fn tsc_ticks(f: impl FnOnce()) -> u64 {
+    let begin = tsc_begin();
+    f();
+    let end = tsc_end();
+    end.wrapping_sub(begin)
+}
+
+// Fewest ticks measured around nothing.
+pub fn tsc_overhead() -> u64 {
+    (0..1000).map(|_| tsc_ticks(|| {})).min().unwrap()
+}
+
+// Measures the rate of the time stamp counter against the monotonic clock.
+pub fn calibrate_tsc() -> Result<f64> {
+    if !cfg!(target_arch = "x86_64") {
+        bail!("the tsc timer is only supported on x86_64");
+    }
+    if !invariant_tsc() {
+        eprintln!("warning: time stamp counter is not invariant");
+    }
+    let begin = (Instant::now(), tsc_begin());
+    let mut end = begin;
+    while end.0 - begin.0 < CALIBRATION {
+        end = (Instant::now(), tsc_end());
+    }
+    let ticks_per_ns = end.1.wrapping_sub(begin.1) as f64 / (end.0 - begin.0).as_secs_f64() / 1e9;
+    eprintln!("tsc: {ticks_per_ns:.3} GHz");
+    Ok(ticks_per_ns)
+}
+
+// Fences keep the measured instructions from being reordered before the first
+// read or after the last read of the counter.
+#[cfg(target_arch = "x86_64")]
+fn tsc_begin() -> u64 {
+    unsafe {
+        _mm_lfence();
+        let tsc = _rdtsc();
+        _mm_lfence();
+        tsc
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn tsc_end() -> u64 {
+    unsafe {
+        let mut aux = 0;
+        let tsc = __rdtscp(&raw mut aux);
+        _mm_lfence();
+        tsc
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn invariant_tsc() -> bool {
+    let max_extended = __cpuid(0x8000_0000).eax;
+    max_extended >= 0x8000_0007 && __cpuid(0x8000_0007).edx & (1 << 8) != 0
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn tsc_begin() -> u64 {
+    unreachable!()
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn tsc_end() -> u64 {
+    unreachable!()
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn invariant_tsc() -> bool {
+    false
+}